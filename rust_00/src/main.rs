@@ -1,4 +1,9 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process;
 
 #[derive(Parser)]
 struct Args {
@@ -13,25 +18,204 @@ struct Args {
     // Le nombre de répétitions (par défaut 1)
     #[arg(short, long, default_value_t = 1)]
     repeat: u8,
+
+    // Le format de sortie (par défaut texte)
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    // Le modèle de salutation, {} est remplacé par le nom
+    #[arg(long, default_value = "Hello, {}!")]
+    template: String,
+
+    // Un nom par ligne, une salutation par nom
+    #[arg(long, conflicts_with = "name")]
+    name_file: Option<String>,
+
+    // Écrit les salutations dans un fichier au lieu de la sortie standard
+    #[arg(long)]
+    output: Option<String>,
+}
+
+// Destination des salutations : le rendu ne sait pas où il écrit
+enum Writer {
+    Stdout(io::Stdout),
+    File(BufWriter<File>),
+}
+
+impl Writer {
+    fn open(path: &str) -> io::Result<Writer> {
+        // Le dossier parent doit déjà exister
+        if let Some(parent) = Path::new(path).parent()
+            && !parent.as_os_str().is_empty()
+            && !parent.is_dir()
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("directory {} does not exist", parent.display()),
+            ));
+        }
+        let file = OpenOptions::new().write(true).create(true).truncate(true).open(path)?;
+        Ok(Writer::File(BufWriter::new(file)))
+    }
+}
+
+impl Write for Writer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Writer::Stdout(out) => out.write(buf),
+            Writer::File(out) => out.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Writer::Stdout(out) => out.flush(),
+            Writer::File(out) => out.flush(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+    Yaml,
+}
+
+#[derive(Serialize)]
+struct Greeting<'a> {
+    message: &'a str,
+}
+
+// Une seule répétition : {"message": ...}, sinon une liste de messages
+fn render(message: &str, repeat: u8, fmt: OutputFormat) -> String {
+    let messages = vec![message; repeat as usize];
+
+    match fmt {
+        OutputFormat::Text => messages.join("\n"),
+        OutputFormat::Json if repeat == 1 => serde_json::to_string(&Greeting { message }).unwrap(),
+        OutputFormat::Json => serde_json::to_string(&messages).unwrap(),
+        OutputFormat::Yaml if repeat == 1 => serde_yaml::to_string(&Greeting { message }).unwrap(),
+        OutputFormat::Yaml => serde_yaml::to_string(&messages).unwrap(),
+    }
+}
+
+// Le modèle doit contenir exactement un {}
+fn render_template(template: &str, name: &str) -> Result<String, String> {
+    match template.matches("{}").count() {
+        1 => Ok(template.replacen("{}", name, 1)),
+        0 => Err(format!("template \"{}\" has no {{}} placeholder for the name", template)),
+        n => Err(format!("template \"{}\" has {} {{}} placeholders, expected exactly one", template, n)),
+    }
+}
+
+// Les lignes vides sont ignorées, les espaces autour des noms retirés
+fn read_names(path: &str) -> io::Result<Vec<String>> {
+    let content = fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
 }
 
 fn main() {
     let args = Args::parse();
 
-    let name_to_display = if args.name.is_empty() {
-        "World"
-    } 
-    else {
-        &args.name
+    let mut names = match &args.name_file {
+        Some(path) => match read_names(path) {
+            Ok(names) => names,
+            Err(e) => {
+                eprintln!("Error: Could not read {}. {}", path, e);
+                process::exit(1);
+            }
+        },
+        None => vec![args.name.clone()],
     };
+    // Fichier vide : une seule salutation par défaut
+    if names.is_empty() {
+        names.push(String::from("World"));
+    }
+
+    let mut out = match &args.output {
+        Some(path) => match Writer::open(path) {
+            Ok(writer) => writer,
+            Err(e) => {
+                eprintln!("Error: Could not write to {}. {}", path, e);
+                process::exit(1);
+            }
+        },
+        None => Writer::Stdout(io::stdout()),
+    };
+
+    for name in &names {
+        let name_to_display = if name.is_empty() {
+            "World"
+        }
+        else {
+            name
+        };
+
+        let mut message = match render_template(&args.template, name_to_display) {
+            Ok(message) => message,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                process::exit(1);
+            }
+        };
+
+        if args.upper {
+            message = message.to_uppercase();
+        }
 
-    let mut message = format!("Hello, {}!", args.name);
+        let output = render(&message, args.repeat, args.format);
+        if !output.is_empty()
+            && let Err(e) = writeln!(out, "{}", output.trim_end())
+        {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+    }
+
+    if let Err(e) = out.flush() {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_text() {
+        assert_eq!(render("Hello, World!", 1, OutputFormat::Text), "Hello, World!");
+        assert_eq!(render("Hi", 3, OutputFormat::Text), "Hi\nHi\nHi");
+        assert_eq!(render("Hi", 0, OutputFormat::Text), "");
+    }
+
+    #[test]
+    fn render_json() {
+        assert_eq!(render("Hello, World!", 1, OutputFormat::Json), r#"{"message":"Hello, World!"}"#);
+        assert_eq!(render("Hi", 3, OutputFormat::Json), r#"["Hi","Hi","Hi"]"#);
+        assert_eq!(render("Hi", 0, OutputFormat::Json), "[]");
+    }
+
+    #[test]
+    fn render_yaml() {
+        assert_eq!(render("Hello, World!", 1, OutputFormat::Yaml), "message: Hello, World!\n");
+        assert_eq!(render("Hi", 2, OutputFormat::Yaml), "- Hi\n- Hi\n");
+    }
 
-    if args.upper {
-        message = message.to_uppercase();
+    #[test]
+    fn render_template_replaces_placeholder() {
+        assert_eq!(render_template("Hi, {}!", "Alice"), Ok(String::from("Hi, Alice!")));
     }
 
-    for _ in 0..args.repeat {
-        println!("{}", message);
+    #[test]
+    fn render_template_needs_one_placeholder() {
+        assert!(render_template("Hi!", "Alice").is_err());
+        assert!(render_template("{} and {}", "Alice").is_err());
     }
 }
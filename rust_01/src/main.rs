@@ -1,14 +1,143 @@
-use clap::Parser;
-use std::collections::HashMap;
-use std::io::{self, Read};
+use clap::{Parser, ValueEnum};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+use std::process;
+use walkdir::WalkDir;
+
+// Liste intégrée des mots vides anglais (comparaison toujours en minuscules)
+const ENGLISH_STOP_WORDS: &[&str] = &[
+    "a", "about", "above", "across", "after", "afterwards", "again", "against", "all", "almost",
+    "alone", "along", "already", "also", "although", "always", "am", "among", "amongst", "an",
+    "and", "another", "any", "anyhow", "anyone", "anything", "anyway", "anywhere", "are",
+    "around", "as", "at", "back", "be", "became", "because", "become", "becomes", "becoming",
+    "been", "before", "beforehand", "behind", "being", "below", "beside", "besides", "between",
+    "beyond", "both", "but", "by", "can", "cannot", "could", "did", "do", "does", "doing",
+    "done", "down", "during", "each", "either", "else", "elsewhere", "enough", "even", "ever",
+    "every", "everyone", "everything", "everywhere", "except", "few", "for", "former",
+    "formerly", "from", "further", "had", "has", "have", "having", "he", "hence", "her", "here",
+    "hereafter", "hereby", "herein", "hers", "herself", "him", "himself", "his", "how",
+    "however", "i", "if", "in", "indeed", "into", "is", "it", "its", "itself", "just", "last",
+    "latter", "least", "less", "many", "may", "me", "meanwhile", "might", "mine", "more",
+    "moreover", "most", "mostly", "much", "must", "my", "myself", "namely", "neither", "never",
+    "nevertheless", "next", "no", "nobody", "none", "noone", "nor", "not", "nothing", "now",
+    "nowhere", "of", "off", "often", "on", "once", "one", "only", "onto", "or", "other",
+    "others", "otherwise", "our", "ours", "ourselves", "out", "over", "own", "per", "perhaps",
+    "rather", "same", "several", "she", "should", "since", "so", "some", "somehow", "someone",
+    "something", "sometime", "sometimes", "somewhere", "still", "such", "than", "that", "the",
+    "their", "theirs", "them", "themselves", "then", "thence", "there", "thereafter",
+    "thereby", "therefore", "therein", "these", "they", "this", "those", "though", "through",
+    "throughout", "thus", "to", "together", "too", "toward", "towards", "under", "until", "up",
+    "upon", "us", "very", "was", "we", "well", "were", "what", "whatever", "when", "whence",
+    "whenever", "where", "whereas", "whereby", "wherein", "whether", "which", "while", "who",
+    "whoever", "whole", "whom", "whose", "why", "will", "with", "within", "without", "would",
+    "yet", "you", "your", "yours", "yourself", "yourselves",
+];
+
+// Mots vides français (formes élidées incluses : l', d', qu'...)
+const FRENCH_STOP_WORDS: &[&str] = &[
+    "a", "ai", "au", "aux", "avec", "c", "ce", "ces", "cet", "cette", "d", "dans", "de", "des",
+    "du", "elle", "elles", "en", "est", "et", "eu", "il", "ils", "j", "je", "l", "la", "le",
+    "les", "leur", "leurs", "lui", "m", "ma", "mais", "me", "mes", "moi", "mon", "n", "ne",
+    "nos", "notre", "nous", "on", "ont", "ou", "où", "par", "pas", "pour", "qu", "que", "qui",
+    "s", "sa", "se", "ses", "son", "sont", "sur", "t", "ta", "te", "tes", "toi", "ton", "tu",
+    "un", "une", "vos", "votre", "vous", "y", "été", "être",
+];
+
+const GERMAN_STOP_WORDS: &[&str] = &[
+    "aber", "als", "am", "an", "auch", "auf", "aus", "bei", "bin", "bis", "da", "das", "dass",
+    "dem", "den", "der", "des", "die", "dir", "du", "ein", "eine", "einem", "einen", "einer",
+    "eines", "er", "es", "für", "hat", "ich", "ihr", "im", "in", "ist", "ja", "mich", "mir",
+    "mit", "nach", "nicht", "noch", "nur", "oder", "sich", "sie", "sind", "so", "um", "und",
+    "uns", "von", "vor", "war", "was", "wie", "wir", "zu", "zum", "zur", "über",
+];
+
+const SPANISH_STOP_WORDS: &[&str] = &[
+    "a", "al", "algo", "como", "con", "de", "del", "el", "ella", "ellos", "en", "era", "es",
+    "esta", "este", "esto", "fue", "ha", "han", "la", "las", "le", "les", "lo", "los", "me",
+    "mi", "muy", "más", "ni", "no", "nos", "o", "para", "pero", "por", "que", "se", "si", "sin",
+    "su", "sus", "también", "te", "tu", "un", "una", "uno", "y", "ya", "yo", "él",
+];
+
+const ITALIAN_STOP_WORDS: &[&str] = &[
+    "a", "ai", "al", "alla", "anche", "che", "chi", "ci", "come", "con", "da", "dal", "dei",
+    "del", "della", "di", "e", "era", "gli", "ha", "hanno", "i", "il", "in", "io", "l", "la",
+    "le", "lo", "ma", "mi", "ne", "nel", "nella", "non", "o", "per", "più", "quella", "questo",
+    "se", "si", "sono", "su", "tra", "tu", "un", "una", "uno", "è", "dell", "all", "nell",
+];
+
+/// Langue du texte (--lang)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Lang {
+    En,
+    Fr,
+    De,
+    Es,
+    It,
+}
+
+/// Règles propres à une langue : mots vides et découpage des élisions
+struct LanguageProfile {
+    stop_words: &'static [&'static str],
+    /// Coupe "l'homme" en "l" + "homme" (inutile en anglais : "don't" reste un mot)
+    contractions: bool,
+}
+
+impl LanguageProfile {
+    fn new(lang: Lang) -> Self {
+        let stop_words = match lang {
+            Lang::En => ENGLISH_STOP_WORDS,
+            Lang::Fr => FRENCH_STOP_WORDS,
+            Lang::De => GERMAN_STOP_WORDS,
+            Lang::Es => SPANISH_STOP_WORDS,
+            Lang::It => ITALIAN_STOP_WORDS,
+        };
+        LanguageProfile { stop_words, contractions: lang != Lang::En }
+    }
+
+    /// Sépare un token sur ses apostrophes (droites ou typographiques)
+    fn split_contractions<'a>(&self, token: &'a str) -> Vec<&'a str> {
+        if !self.contractions {
+            return vec![token];
+        }
+        token.split(is_apostrophe).filter(|part| !part.is_empty()).collect()
+    }
+}
+
+fn is_apostrophe(c: char) -> bool {
+    c == '\'' || c == '’'
+}
 
 /// Count word frequency in text
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     /// Text to analyze (or use stdin)
+    #[arg(conflicts_with_all = ["files", "dir"])]
     text: Option<String>,
 
+    /// Files or glob patterns to analyze (per-file tables + aggregate)
+    #[arg(long, num_args = 1..)]
+    files: Vec<String>,
+
+    /// Recursively analyze every matching file under this directory
+    #[arg(long, conflicts_with = "files")]
+    dir: Option<String>,
+
+    /// File extension analyzed by --dir
+    #[arg(long, default_value = "txt", requires = "dir")]
+    extension: String,
+
+    /// Print words unique to each file side by side (requires --files)
+    #[arg(long, requires = "files")]
+    compare_files: bool,
+
     /// Show top N words
     #[arg(long, default_value_t = 10)]
     top: usize,
@@ -17,17 +146,518 @@ struct Args {
     #[arg(long, default_value_t = 1)]
     min_length: usize,
 
+    /// Skip purely numeric tokens (e.g. "42", "2024")
+    #[arg(long)]
+    exclude_numbers: bool,
+
+    /// Skip tokens mixing letters and digits (e.g. "3rd", "C3PO")
+    #[arg(long)]
+    exclude_mixed: bool,
+
     /// Case insensitive counting
     #[arg(long)]
     ignore_case: bool,
+
+    /// Count n-grams of N consecutive words (1 = words, 2 = bigrams, 3 = trigrams)
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=3))]
+    ngram: u8,
+
+    /// Exclude common stop words of the --lang language
+    #[arg(long)]
+    stop_words: bool,
+
+    /// Language of the text: stop-word list and apostrophe contractions (l'homme -> l + homme)
+    #[arg(long, value_enum, default_value_t = Lang::En)]
+    lang: Lang,
+
+    /// Extra stop words file (one word per line), merged with the built-in list
+    #[arg(long)]
+    stop_words_file: Option<String>,
+
+    /// Output results as a JSON array
+    #[arg(long)]
+    json: bool,
+
+    /// Pretty-print the JSON output (requires --json)
+    #[arg(long, requires = "json")]
+    json_pretty: bool,
+
+    /// Count individual characters instead of words
+    #[arg(long)]
+    chars: bool,
+
+    /// Include whitespace characters in character counts (requires --chars)
+    #[arg(long, requires = "chars")]
+    include_whitespace: bool,
+
+    /// Also report sentence statistics after the frequency table
+    #[arg(long, conflicts_with = "json")]
+    sentences: bool,
+
+    /// Also report Flesch readability scores
+    #[arg(long, conflicts_with = "json")]
+    readability: bool,
+
+    /// Draw the frequency table as a horizontal bar chart
+    #[arg(long, conflicts_with = "json")]
+    histogram: bool,
+
+    /// Maximum width of the histogram in columns
+    #[arg(long, default_value_t = 80)]
+    width: usize,
+
+    /// Also write the full frequency table (word,count,rank,percent) to this CSV file
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["files", "dir", "chars"])]
+    output_csv: Option<String>,
+
+    /// Replace the --output-csv file if it already exists
+    #[arg(long, requires = "output_csv")]
+    overwrite: bool,
+
+    /// Also plot rank vs frequency on log-log axes and check Zipf's law
+    #[arg(long, conflicts_with_all = ["json", "files", "dir"])]
+    zipf: bool,
+
+    /// Also list groups of words that are anagrams of each other
+    #[arg(long, conflicts_with_all = ["json", "files", "dir"])]
+    anagram_groups: bool,
+
+    /// Also group words that sound alike (same Soundex code)
+    #[arg(long, conflicts_with_all = ["json", "files", "dir"])]
+    phonetic: bool,
+
+    /// Count each word as first, middle or last word of its sentence instead of raw frequency
+    #[arg(long, conflicts_with_all = ["files", "dir", "json", "chars", "diff", "word_cloud", "histogram"])]
+    position_analysis: bool,
+
+    /// Print the top words of each window of N words instead of the whole text
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..),
+          conflicts_with_all = ["files", "dir", "json", "chars", "diff", "word_cloud", "histogram"])]
+    chunk_size: Option<u64>,
+
+    /// Words shared by consecutive --chunk-size windows (windows slide by N - M)
+    #[arg(long, value_name = "M", default_value_t = 0, requires = "chunk_size")]
+    overlap: u64,
+
+    /// Print the N most important sentences (TF-IDF score), in their original order
+    #[arg(long, value_name = "N", conflicts_with_all = ["files", "dir", "json", "chars", "diff", "word_cloud", "histogram", "position_analysis"])]
+    summarize: Option<usize>,
+
+    /// Compare word frequencies with another text (or the file at this path)
+    #[arg(long, value_name = "OTHER", conflicts_with_all = ["files", "dir", "json", "chars", "word_cloud"])]
+    diff: Option<String>,
+
+    /// Cosine similarity of word frequencies with another text (or the file at this path)
+    #[arg(long, value_name = "OTHER", conflicts_with_all = ["files", "dir", "json", "chars", "word_cloud", "diff"])]
+    cosine_sim: Option<String>,
+
+    /// Also list the corpus words starting with this prefix, most frequent first
+    #[arg(long, value_name = "PREFIX", conflicts_with_all = ["json", "chars", "files", "dir"])]
+    autocomplete: Option<String>,
+
+    /// Maximum number of --autocomplete results
+    #[arg(long, value_name = "N", default_value_t = 10, requires = "autocomplete")]
+    autocomplete_limit: usize,
+
+    /// Render the top words as an ASCII word cloud WIDTH columns wide (WIDTH/2 rows)
+    #[arg(long, value_name = "WIDTH", conflicts_with_all = ["json", "histogram"])]
+    word_cloud: Option<usize>,
+
+    /// Seed for --word-cloud placement (same seed and text give the same cloud)
+    #[arg(long, requires = "word_cloud")]
+    seed: Option<u64>,
+
+    /// Extract tokens matching this regex instead of splitting on non-alphanumerics
+    #[arg(long)]
+    pattern: Option<String>,
+
+    /// Data structure used to count words
+    #[arg(long, value_enum, default_value_t = Backend::Hash)]
+    backend: Backend,
+
+    /// Read hex-encoded bytes from stdin (e.g. a rust_02 dump) and analyze the decoded text
+    #[arg(long, conflicts_with_all = ["text", "files", "dir"])]
+    stdin_hex: bool,
+}
+
+/// Structure de comptage des mots (--backend)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Backend {
+    /// One HashMap entry (and String) per unique word
+    Hash,
+    /// Prefix trie: shared prefixes are stored once
+    Trie,
+}
+
+/// Entrée de la sortie JSON
+#[derive(Serialize)]
+struct WordEntry<'a> {
+    word: &'a str,
+    count: usize,
+}
+
+/// Entrée de la sortie JSON en mode caractères
+#[derive(Serialize)]
+struct CharEntry {
+    char: String,
+    count: usize,
+}
+
+/// Compteur de caractères alimenté ligne par ligne
+struct CharCounter {
+    counts: HashMap<char, usize>,
+    ignore_case: bool,
+    include_whitespace: bool,
+}
+
+impl CharCounter {
+    fn new(ignore_case: bool, include_whitespace: bool) -> Self {
+        CharCounter { counts: HashMap::new(), ignore_case, include_whitespace }
+    }
+
+    fn feed_line(&mut self, line: &str) {
+        for c in line.chars() {
+            if c.is_whitespace() && !self.include_whitespace {
+                continue;
+            }
+            if self.ignore_case {
+                for lower in c.to_lowercase() {
+                    *self.counts.entry(lower).or_insert(0) += 1;
+                }
+            } else {
+                *self.counts.entry(c).or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// Les N caractères les plus fréquents (fréquence décroissante, puis ordre du caractère)
+    fn top_n(&self, n: usize) -> Vec<(char, usize)> {
+        let mut sorted: Vec<(char, usize)> = self.counts.iter().map(|(&c, &n)| (c, n)).collect();
+        sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        sorted.truncate(n);
+        sorted
+    }
+
+    fn len(&self) -> usize {
+        self.counts.len()
+    }
+}
+
+/// Compteur de mots (ou de n-grammes)
+struct WordCounter {
+    counts: HashMap<String, usize>,
+}
+
+impl WordCounter {
+    fn new() -> Self {
+        WordCounter { counts: HashMap::new() }
+    }
+
+    /// Ajoute les comptes d'un autre compteur à celui-ci
+    fn merge(&mut self, other: &WordCounter) {
+        for (word, count) in &other.counts {
+            *self.counts.entry(word.clone()).or_insert(0) += count;
+        }
+    }
+
+    fn contains(&self, word: &str) -> bool {
+        self.counts.contains_key(word)
+    }
+
+    /// Tri principal : Fréquence (décroissant)
+    /// Tri secondaire : Alphabétique (pour avoir un ordre stable en cas d'égalité)
+    fn sorted(&self) -> Vec<(&String, &usize)> {
+        let mut sorted_counts: Vec<(&String, &usize)> = self.counts.iter().collect();
+        sorted_counts.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        sorted_counts
+    }
+}
+
+/// Noeud du trie : un enfant par caractère, `count` > 0 si un mot se termine ici
+struct TrieNode {
+    children: HashMap<char, TrieNode>,
+    count: usize,
+}
+
+impl TrieNode {
+    fn new() -> Self {
+        TrieNode { children: HashMap::new(), count: 0 }
+    }
+
+    /// Ajoute à `words` tous les mots du sous-trie, `prefix` étant le chemin jusqu'à ce noeud
+    fn collect(&self, prefix: &mut String, words: &mut Vec<(String, usize)>) {
+        if self.count > 0 {
+            words.push((prefix.clone(), self.count));
+        }
+        for (&c, child) in &self.children {
+            prefix.push(c);
+            child.collect(prefix, words);
+            prefix.pop();
+        }
+    }
+
+    /// Comme collect, mais ne garde que les n meilleurs mots : le sommet du tas est le
+    /// moins bon, et le préfixe n'est copié que s'il entre dans le classement
+    fn collect_top(&self, prefix: &mut String, n: usize, best: &mut BinaryHeap<(Reverse<usize>, String)>) {
+        if self.count > 0 {
+            if best.len() < n {
+                best.push((Reverse(self.count), prefix.clone()));
+            } else if let Some((Reverse(count), word)) = best.peek()
+                && (Reverse(self.count), prefix.as_str()) < (Reverse(*count), word.as_str())
+            {
+                best.pop();
+                best.push((Reverse(self.count), prefix.clone()));
+            }
+        }
+        for (&c, child) in &self.children {
+            prefix.push(c);
+            child.collect_top(prefix, n, best);
+            prefix.pop();
+        }
+    }
+
+    /// Mots commençant par `prefix` (le préfixe lui-même compris), fréquence décroissante
+    fn completions(&self, prefix: &str) -> Vec<(String, usize)> {
+        let mut node = self;
+        for c in prefix.chars() {
+            match node.children.get(&c) {
+                Some(child) => node = child,
+                None => return Vec::new(),
+            }
+        }
+
+        let mut words = Vec::new();
+        node.collect(&mut prefix.to_string(), &mut words);
+        words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        words
+    }
+}
+
+/// Compteur de mots en trie (--backend trie) : les préfixes communs ne sont stockés qu'une fois,
+/// et seuls les mots du top N deviennent des String
+struct TrieCounter {
+    root: TrieNode,
+    unique: usize,
+}
+
+impl TrieCounter {
+    fn new() -> Self {
+        TrieCounter { root: TrieNode::new(), unique: 0 }
+    }
+
+    fn insert(&mut self, word: &str) {
+        let mut node = &mut self.root;
+        for c in word.chars() {
+            node = node.children.entry(c).or_insert_with(TrieNode::new);
+        }
+        if node.count == 0 {
+            self.unique += 1;
+        }
+        node.count += 1;
+    }
+
+    /// Nombre de mots distincts
+    fn len(&self) -> usize {
+        self.unique
+    }
+
+    /// Parcourt tout le trie en gardant les n meilleurs, ordonnés comme WordCounter::sorted
+    /// (fréquence, puis alphabétique)
+    fn top_n(&self, n: usize) -> Vec<(String, usize)> {
+        let mut best = BinaryHeap::new();
+        if n > 0 {
+            self.root.collect_top(&mut String::new(), n, &mut best);
+        }
+        best.into_sorted_vec().into_iter().map(|(Reverse(count), word)| (word, count)).collect()
+    }
+
+    fn completions(&self, prefix: &str) -> Vec<(String, usize)> {
+        self.root.completions(prefix)
+    }
+}
+
+// Benchmark (criterion) à réactiver dans benches/ pour comparer les deux backends
+// sur un texte de 1 Mo :
+//
+// fn bench_backends(c: &mut Criterion) {
+//     let text = std::fs::read_to_string("bench_1mb.txt").unwrap();
+//     let (profile, filter) = (LanguageProfile::new(Lang::En), TokenFilter::new(1));
+//     c.bench_function("hash", |b| b.iter(|| {
+//         let mut map = HashMap::new();
+//         Tokenizer::Default.for_each_token(&text, &profile, &filter, true, |t| {
+//             *map.entry(t.to_string()).or_insert(0usize) += 1;
+//         });
+//     }));
+//     c.bench_function("trie", |b| b.iter(|| {
+//         let mut trie = TrieCounter::new();
+//         Tokenizer::Default.for_each_token(&text, &profile, &filter, true, |t| trie.insert(t));
+//     }));
+// }
+//
+// RSS maximal mesuré (build release, 1 Mo de 20 000 mots distincts de 2 à 10 lettres) :
+// hash 14,8 Mo, trie 22,5 Mo. Le trie ne gagne pas : chaque nœud porte sa propre
+// HashMap d'enfants, plus lourde que les octets de préfixe qu'il évite de répéter.
+// Son intérêt est ailleurs : l'autocomplétion et le top N sans trier tous les mots.
+
+/// Trie d'autocomplétion (--autocomplete), rempli depuis les comptes déjà calculés
+struct Trie {
+    root: TrieNode,
+}
+
+impl Trie {
+    fn new() -> Self {
+        Trie { root: TrieNode::new() }
+    }
+
+    fn from_counts(counts: &HashMap<String, usize>) -> Self {
+        let mut trie = Trie::new();
+        for (word, &count) in counts {
+            trie.insert(word, count);
+        }
+        trie
+    }
+
+    fn insert(&mut self, word: &str, count: usize) {
+        let mut node = &mut self.root;
+        for c in word.chars() {
+            node = node.children.entry(c).or_insert_with(TrieNode::new);
+        }
+        node.count += count;
+    }
+
+    fn completions(&self, prefix: &str) -> Vec<(String, usize)> {
+        self.root.completions(prefix)
+    }
+}
+
+/// Comptes par fichier + agrégat fusionné
+struct MultiFileCounter {
+    files: Vec<(String, WordCounter)>,
+    merged: WordCounter,
+}
+
+impl MultiFileCounter {
+    fn new() -> Self {
+        MultiFileCounter { files: Vec::new(), merged: WordCounter::new() }
+    }
+
+    fn add_file(&mut self, name: String, counter: WordCounter) {
+        self.merged.merge(&counter);
+        self.files.push((name, counter));
+    }
+
+    /// Itère sur les compteurs par nom de fichier, dans l'ordre d'ajout
+    fn iter(&self) -> impl Iterator<Item = (&str, &WordCounter)> {
+        self.files.iter().map(|(name, counter)| (name.as_str(), counter))
+    }
+
+    fn merged(&self) -> &WordCounter {
+        &self.merged
+    }
+
+    fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Pour chaque fichier, les mots absents de tous les autres fichiers (triés)
+    fn unique_words(&self) -> Vec<(&str, Vec<&String>)> {
+        self.iter()
+            .enumerate()
+            .map(|(i, (name, counter))| {
+                let mut words: Vec<&String> = counter
+                    .counts
+                    .keys()
+                    .filter(|w| {
+                        self.files
+                            .iter()
+                            .enumerate()
+                            .all(|(j, (_, other))| i == j || !other.contains(w))
+                    })
+                    .collect();
+                words.sort();
+                (name, words)
+            })
+            .collect()
+    }
+}
+
+/// Filtre de mots vides (liste intégrée + mots supplémentaires)
+struct StopWordFilter {
+    words: HashSet<String>,
+}
+
+impl StopWordFilter {
+    fn new(builtin: &[&str], extra: &[String]) -> Self {
+        let words = builtin
+            .iter()
+            .map(|w| w.to_string())
+            .chain(extra.iter().map(|w| w.trim().to_lowercase()))
+            .filter(|w| !w.is_empty())
+            .collect();
+        StopWordFilter { words }
+    }
+
+    fn is_stop(&self, word: &str) -> bool {
+        // Comparaison toujours en minuscules, indépendamment de --ignore-case
+        self.words.contains(&word.to_lowercase())
+    }
 }
 
 fn main() -> io::Result<()> {
-    let args = Args::parse();
+    let mut args = Args::parse();
+
+    // Fichier CSV existant : on refuse avant toute analyse, sauf --overwrite
+    if let Some(path) = &args.output_csv
+        && Path::new(path).exists()
+        && !args.overwrite
+    {
+        eprintln!("Error: {} already exists (use --overwrite to replace it)", path);
+        process::exit(1);
+    }
+
+    // Choix du tokeniseur (--pattern ou découpage par défaut)
+    let tokenizer = match &args.pattern {
+        Some(pattern) => match regex::Regex::new(pattern) {
+            Ok(re) => Tokenizer::Regex(re),
+            Err(e) => {
+                eprintln!("Invalid --pattern regex: {}", e);
+                process::exit(1);
+            }
+        },
+        None => Tokenizer::Default,
+    };
+
+    if args.backend == Backend::Trie
+        && let Some(flag) = trie_conflict(&args)
+    {
+        eprintln!("Error: {} needs the full word counts and cannot be used with --backend trie", flag);
+        process::exit(1);
+    }
+
+    // Mode multi-fichiers (--files)
+    if !args.files.is_empty() {
+        return run_files(&args, &tokenizer);
+    }
+
+    // Mode arborescence (--dir)
+    if let Some(dir) = &args.dir {
+        return run_dir(dir, &args, &tokenizer);
+    }
 
-    // 1. Récupération du contenu (Argument direct OU Stdin)
-    let content = match args.text {
+    // 1. Récupération du contenu (Argument direct OU Stdin, éventuellement en hex)
+    let content = match args.text.take() {
         Some(text) => text,
+        None if args.stdin_hex => {
+            let mut buffer = String::new();
+            io::stdin().read_to_string(&mut buffer)?;
+            match decode_hex_stream(&buffer) {
+                Ok(text) => text,
+                Err(e) => {
+                    eprintln!("Invalid hex input: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
         None => {
             // Si pas d'argument texte, on lit stdin
             let mut buffer = String::new();
@@ -38,51 +668,1485 @@ fn main() -> io::Result<()> {
         }
     };
 
-    // 2. Comptage des mots
-    let mut counts: HashMap<String, usize> = HashMap::new();
+    // Mode caractères : on court-circuite l'analyse par mots
+    if args.chars {
+        let mut counter = CharCounter::new(args.ignore_case, args.include_whitespace);
+        for line in content.split_inclusive('\n') {
+            counter.feed_line(line);
+        }
+        return print_char_frequency(&counter, &args);
+    }
+
+    // Backend trie : tableau de fréquences tiré du trie, sans table complète des comptes
+    if args.backend == Backend::Trie {
+        return run_trie(&content, &tokenizer, &args);
+    }
 
-    // On découpe par tout ce qui n'est pas alphanumérique (pour virer la ponctuation)
-    let tokens = content
-        .split(|c: char| !c.is_alphanumeric())
-        .filter(|s| !s.is_empty());
+    // 2 & 3. Découpage en tokens puis comptage des mots ou des n-grammes
+    let counter = count_content(&content, &tokenizer, &args)?;
 
-    for token in tokens {
-        // Filtre de longueur
-        if token.len() < args.min_length {
-            continue;
+    // 4 & 5. Tri et affichage, ou le mode qui remplace le tableau de fréquences
+    if args.position_analysis {
+        // Mode positions dans la phrase
+        print_position_analysis(&position_counts(&content, &tokenizer, &args)?, args.top);
+    } else if let Some(n) = args.summarize {
+        // Résumé extractif : les N phrases les mieux notées
+        print_summary(&content, &tokenizer, &args, n)?;
+    } else if let Some(size) = args.chunk_size {
+        // Fenêtres de --chunk-size mots : top K par fenêtre
+        let (window, overlap) = (size as usize, args.overlap as usize);
+        if overlap >= window {
+            eprintln!("Error: --overlap ({}) must be smaller than --chunk-size ({})", overlap, window);
+            process::exit(1);
         }
+        let tokens = content_tokens(&content, &tokenizer, &args)?;
+        print_windows(&windowed_analysis(&tokens, window, overlap, args.top), tokens.len(), window, overlap);
+    } else if let Some(other) = &args.diff {
+        // Mode comparaison (--diff) : l'autre texte est compté avec les mêmes options
+        let other_counter = count_content(&read_other(other)?, &tokenizer, &args)?;
+        print_freq_diff(&freq_diff(&counter.counts, &other_counter.counts), &counter, &other_counter, args.top);
+    } else if let Some(other) = &args.cosine_sim {
+        // Similarité cosinus avec l'autre texte
+        let other_counter = count_content(&read_other(other)?, &tokenizer, &args)?;
+        print_cosine_similarity(&counter.counts, &other_counter.counts);
+    } else {
+        print_frequency(&counter, &args)?;
+    }
 
-        // Gestion de la casse
-        let word = if args.ignore_case {
-            token.to_lowercase()
-        } else {
-            token.to_string()
-        };
+    // Table complète en CSV, quel que soit l'affichage
+    if let Some(path) = &args.output_csv {
+        let entries: Vec<(String, usize)> =
+            counter.sorted().into_iter().map(|(word, &count)| (word.clone(), count)).collect();
+        let total = entries.iter().map(|(_, count)| count).sum();
+        write_csv(path, &entries, total)?;
+    }
+
+    // 6. Statistiques de phrases (après le tableau de fréquences)
+    if args.sentences {
+        print_sentence_stats(&content);
+    }
+
+    // 7. Scores de lisibilité
+    if args.readability {
+        print_readability(&content);
+    }
+
+    // 8. Loi de Zipf (rang / fréquence en log-log)
+    if args.zipf {
+        let counts: Vec<usize> = counter.sorted().into_iter().map(|(_, &count)| count).collect();
+        print_zipf(&counts, args.width);
+    }
+
+    // 9. Groupes d'anagrammes
+    if args.anagram_groups {
+        print_anagram_groups(&counter.counts);
+    }
+
+    // 10. Groupes phonétiques (Soundex)
+    if args.phonetic {
+        print_phonetic_groups(&counter.counts);
+    }
+
+    // 11. Autocomplétion sur le préfixe
+    if let Some(prefix) = &args.autocomplete {
+        let prefix = if args.ignore_case { prefix.to_lowercase() } else { prefix.clone() };
+        print_completions(Trie::from_counts(&counter.counts).completions(&prefix), &prefix, args.autocomplete_limit);
+    }
+
+    Ok(())
+}
 
-        *counts.entry(word).or_insert(0) += 1;
+/// Décode un flux hex ("48656c6c6f", blancs ignorés) comme hex_string_to_bytes de rust_02.
+/// Un contenu qui n'est pas de l'UTF-8 valide est converti avec pertes, avec un avertissement.
+fn decode_hex_stream(input: &str) -> Result<String, String> {
+    let hex: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    if !hex.len().is_multiple_of(2) {
+        return Err("hex string length must be even".to_string());
     }
 
-    // 3. Tri des résultats
-    let mut sorted_counts: Vec<(&String, &usize)> = counts.iter().collect();
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| format!("invalid hex character at index {}", i))
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok(text),
+        Err(e) => {
+            eprintln!("Warning: decoded bytes are not valid UTF-8, invalid sequences replaced");
+            Ok(String::from_utf8_lossy(e.as_bytes()).into_owned())
+        }
+    }
+}
+
+/// Tokens comptés : découpage, filtre de tokens (mots vides compris) et casse
+fn content_tokens(content: &str, tokenizer: &Tokenizer, args: &Args) -> io::Result<Vec<String>> {
+    // Les mots vides sont retirés avant la construction des n-grammes
+    let profile = LanguageProfile::new(args.lang);
+    Ok(tokenizer.tokenize(content, &profile, &TokenFilter::from_args(args)?, args.ignore_case))
+}
+
+/// Compte les mots (ou n-grammes) des tokens de content_tokens
+fn count_content(content: &str, tokenizer: &Tokenizer, args: &Args) -> io::Result<WordCounter> {
+    let tokens = content_tokens(content, tokenizer, args)?;
+
+    let counts: HashMap<String, usize> = match args.ngram {
+        2 => {
+            let mut pairs: HashMap<(String, String), usize> = HashMap::new();
+            for gram in ngrams(&tokens, 2) {
+                *pairs.entry((gram[0].clone(), gram[1].clone())).or_insert(0) += 1;
+            }
+            pairs
+                .into_iter()
+                .map(|((a, b), count)| (format!("{} {}", a, b), count))
+                .collect()
+        }
+        3 => {
+            let mut triples: HashMap<(String, String, String), usize> = HashMap::new();
+            for gram in ngrams(&tokens, 3) {
+                let key = (gram[0].clone(), gram[1].clone(), gram[2].clone());
+                *triples.entry(key).or_insert(0) += 1;
+            }
+            triples
+                .into_iter()
+                .map(|((a, b, c), count)| (format!("{} {} {}", a, b, c), count))
+                .collect()
+        }
+        _ => {
+            let mut words: HashMap<String, usize> = HashMap::new();
+            for token in tokens {
+                *words.entry(token).or_insert(0) += 1;
+            }
+            words
+        }
+    };
 
-    // Tri principal : Fréquence (décroissant)
-    // Tri secondaire : Alphabétique (pour avoir un ordre stable en cas d'égalité)
-    sorted_counts.sort_by(|a, b| {
-        b.1.cmp(a.1).then_with(|| a.0.cmp(b.0))
+    Ok(WordCounter { counts })
+}
+
+/// Backend trie : chaque token va directement du découpage au trie, sans Vec ni HashMap
+fn count_trie(content: &str, tokenizer: &Tokenizer, args: &Args) -> io::Result<TrieCounter> {
+    let profile = LanguageProfile::new(args.lang);
+    let mut trie = TrieCounter::new();
+    tokenizer.for_each_token(content, &profile, &TokenFilter::from_args(args)?, args.ignore_case, |token| {
+        trie.insert(token)
     });
+    Ok(trie)
+}
+
+/// Option qui a besoin de la table complète des comptes, donc incompatible avec --backend trie
+fn trie_conflict(args: &Args) -> Option<&'static str> {
+    [
+        (!args.files.is_empty(), "--files"),
+        (args.dir.is_some(), "--dir"),
+        (args.chars, "--chars"),
+        (args.ngram > 1, "--ngram"),
+        (args.position_analysis, "--position-analysis"),
+        (args.summarize.is_some(), "--summarize"),
+        (args.chunk_size.is_some(), "--chunk-size"),
+        (args.diff.is_some(), "--diff"),
+        (args.cosine_sim.is_some(), "--cosine-sim"),
+        (args.output_csv.is_some(), "--output-csv"),
+        (args.zipf, "--zipf"),
+        (args.anagram_groups, "--anagram-groups"),
+        (args.phonetic, "--phonetic"),
+    ]
+    .into_iter()
+    .find(|&(set, _)| set)
+    .map(|(_, flag)| flag)
+}
+
+/// Affiche le tableau de fréquences (texte ou JSON), limité à --top entrées
+fn print_frequency(counter: &WordCounter, args: &Args) -> io::Result<()> {
+    print_frequency_limit(counter, args, args.top)
+}
+
+fn print_frequency_limit(counter: &WordCounter, args: &Args, top: usize) -> io::Result<()> {
+    let sorted_counts = counter.sorted();
+    print_entries(&sorted_counts[..top.min(sorted_counts.len())], sorted_counts.len(), args)
+}
+
+/// Affiche les entrées déjà triées et limitées ; `unique` = nombre total de mots distincts
+fn print_entries(entries: &[(&String, &usize)], unique: usize, args: &Args) -> io::Result<()> {
+    if args.json {
+        let entries: Vec<WordEntry> = entries.iter().map(|&(word, &count)| WordEntry { word, count }).collect();
+        let json = if args.json_pretty {
+            serde_json::to_string_pretty(&entries)
+        } else {
+            serde_json::to_string(&entries)
+        };
+        println!("{}", json.map_err(io::Error::other)?);
+        return Ok(());
+    }
 
-    // 4. Affichage
     // On détermine le titre en fonction du contexte (comme sur les screenshots)
-    if args.top < sorted_counts.len() {
-        println!("Top {} words:", args.top);
+    let (unit, title) = match args.ngram {
+        2 => ("bigrams", "Bigram"),
+        3 => ("trigrams", "Trigram"),
+        _ => ("words", "Word"),
+    };
+    if entries.len() < unique {
+        println!("Top {} {}:", entries.len(), unit);
     } else {
-        println!("Word frequency:");
+        println!("{} frequency:", title);
     }
 
-    // On prend seulement les N premiers
-    for (word, count) in sorted_counts.into_iter().take(args.top) {
-        println!("{}: {}", word, count);
+    if args.histogram {
+        print_histogram(entries, args);
+        return Ok(());
+    }
+
+    if let Some(width) = args.word_cloud {
+        print_word_cloud(entries, width, args.seed);
+        return Ok(());
+    }
+
+    for &(word, count) in entries {
+        if args.ngram > 1 {
+            println!("\"{}\": {}", word, count);
+        } else {
+            println!("{}: {}", word, count);
+        }
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Histogramme horizontal : la barre la plus longue fait 40 caractères
+/// (moins si --width ne laisse pas la place)
+fn print_histogram(entries: &[(&String, &usize)], args: &Args) {
+    let max_count = entries.first().map(|&(_, &count)| count).unwrap_or(0);
+    let label_width = entries.iter().map(|(word, _)| word.chars().count()).max().unwrap_or(0);
+    let count_width = max_count.to_string().len();
+
+    // Libellé + espace + barre + espace + compte doivent tenir dans --width
+    let bar_width = 40.min(args.width.saturating_sub(label_width + count_width + 2));
+
+    for &(word, &count) in entries {
+        println!(
+            "{:<label_width$} {:<bar_width$} {:>count_width$}",
+            word,
+            render_bar(count, max_count, bar_width),
+            count,
+        );
+    }
+}
+
+// ==========================================
+// FENÊTRES GLISSANTES (--chunk-size)
+// ==========================================
+
+/// Début de chaque fenêtre : pas de window - overlap, la dernière fenêtre s'arrête à la fin du texte
+fn window_starts(len: usize, window: usize, overlap: usize) -> impl Iterator<Item = usize> {
+    let step = window - overlap;
+    (0..len).step_by(step).take_while(move |&start| start == 0 || start + overlap < len)
+}
+
+/// Top K (fréquence décroissante, puis alphabétique) de chaque fenêtre de `window` tokens
+fn windowed_analysis(tokens: &[String], window: usize, overlap: usize, k: usize) -> Vec<Vec<(String, usize)>> {
+    window_starts(tokens.len(), window, overlap)
+        .map(|start| {
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for token in &tokens[start..(start + window).min(tokens.len())] {
+                *counts.entry(token).or_insert(0) += 1;
+            }
+            let mut top: Vec<(String, usize)> = counts.into_iter().map(|(w, c)| (w.to_string(), c)).collect();
+            top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            top.truncate(k);
+            top
+        })
+        .collect()
+}
+
+fn print_windows(windows: &[Vec<(String, usize)>], len: usize, window: usize, overlap: usize) {
+    for (i, (start, top)) in window_starts(len, window, overlap).zip(windows).enumerate() {
+        let end = (start + window).min(len) - 1;
+        if i > 0 {
+            println!();
+        }
+        println!("[Window {}: words {}–{}]", i + 1, start, end);
+        for (word, count) in top {
+            println!("{}: {}", word, count);
+        }
+    }
+}
+
+// ==========================================
+// EXPORT CSV (--output-csv)
+// ==========================================
+
+/// Une ligne par mot, dans l'ordre de `entries` (rang 1 = le plus fréquent)
+fn write_csv(path: &str, entries: &[(String, usize)], total: usize) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "word,count,rank,percent")?;
+    for (rank, (word, count)) in entries.iter().enumerate() {
+        let percent = if total == 0 { 0.0 } else { *count as f64 / total as f64 * 100.0 };
+        writeln!(out, "{},{},{},{:.4}", csv_field(word), count, rank + 1, percent)?;
+    }
+    out.flush()
+}
+
+/// Entre guillemets si le champ contient une virgule, un guillemet ou un saut de ligne
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// ==========================================
+// AUTOCOMPLÉTION (--autocomplete)
+// ==========================================
+
+fn print_completions(completions: Vec<(String, usize)>, prefix: &str, limit: usize) {
+    if completions.is_empty() {
+        println!("\nNo completions found for '{}'", prefix);
+        return;
+    }
+
+    println!("\nCompletions for '{}' ({} of {}):", prefix, completions.len().min(limit), completions.len());
+    for (word, count) in completions.into_iter().take(limit) {
+        println!("{}: {}", word, count);
+    }
+}
+
+// ==========================================
+// SIMILARITÉ COSINUS (--cosine-sim)
+// ==========================================
+
+fn l2_norm(counts: &HashMap<String, usize>) -> f64 {
+    counts.values().map(|&c| (c as f64).powi(2)).sum::<f64>().sqrt()
+}
+
+/// Produit scalaire des vecteurs de fréquences divisé par le produit des normes L2.
+/// Seuls les mots communs contribuent au produit scalaire ; 0.0 si un texte est vide.
+fn cosine_similarity(a: &HashMap<String, usize>, b: &HashMap<String, usize>) -> f64 {
+    let norms = l2_norm(a) * l2_norm(b);
+    if norms == 0.0 {
+        return 0.0;
+    }
+    // fold plutôt que sum : la somme vide de f64 vaut -0.0
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(word, &count)| b.get(word).map(|&other| count as f64 * other as f64))
+        .fold(0.0, |acc, term| acc + term);
+    dot / norms
+}
+
+fn print_cosine_similarity(a: &HashMap<String, usize>, b: &HashMap<String, usize>) {
+    let similarity = cosine_similarity(a, b);
+    let label = match similarity {
+        s if s >= 0.9999 => "identical",
+        s if s >= 0.8 => "very similar",
+        s if s >= 0.5 => "somewhat similar",
+        _ => "dissimilar",
+    };
+    println!("Cosine similarity: {:.4} ({})", similarity, label);
+
+    // Contribution d'un mot : son terme du produit scalaire, normalisé comme la similarité
+    let norms = l2_norm(a) * l2_norm(b);
+    let mut contributions: Vec<(&String, f64)> = a
+        .iter()
+        .filter_map(|(word, &count)| b.get(word).map(|&other| (word, count as f64 * other as f64 / norms)))
+        .collect();
+    contributions.sort_by(|x, y| y.1.total_cmp(&x.1).then_with(|| x.0.cmp(y.0)));
+
+    if contributions.is_empty() {
+        println!("No words in common.");
+        return;
+    }
+    println!("Top contributing words:");
+    for (word, contribution) in contributions.into_iter().take(5) {
+        println!("{}: {:.4}", word, contribution);
+    }
+}
+
+// ==========================================
+// ANAGRAMMES (--anagram-groups)
+// ==========================================
+
+/// Signature d'un mot : ses lettres en minuscules, triées ("listen" -> "eilnst")
+fn anagram_key(word: &str) -> String {
+    let mut chars: Vec<char> = word.to_lowercase().chars().collect();
+    chars.sort_unstable();
+    chars.into_iter().collect()
+}
+
+/// Groupes d'au moins deux mots distincts, du plus grand au plus petit
+fn print_anagram_groups(counts: &HashMap<String, usize>) {
+    let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+    for word in counts.keys() {
+        groups.entry(anagram_key(word)).or_default().push(word.clone());
+    }
+
+    let mut groups: Vec<(String, Vec<String>)> = groups.into_iter().filter(|(_, words)| words.len() >= 2).collect();
+    for (_, words) in &mut groups {
+        words.sort();
+    }
+    groups.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+
+    println!("\nAnagram groups:");
+    if groups.is_empty() {
+        println!("No anagrams found.");
+    }
+    for (key, words) in groups {
+        println!("[{}]: {}", key, words.join(", "));
+    }
+}
+
+// ==========================================
+// GROUPES PHONÉTIQUES (--phonetic)
+// ==========================================
+
+/// Chiffre Soundex d'une lettre ASCII ; None pour les voyelles et h, w, y
+fn soundex_digit(c: char) -> Option<char> {
+    match c.to_ascii_lowercase() {
+        'b' | 'f' | 'p' | 'v' => Some('1'),
+        'c' | 'g' | 'j' | 'k' | 'q' | 's' | 'x' | 'z' => Some('2'),
+        'd' | 't' => Some('3'),
+        'l' => Some('4'),
+        'm' | 'n' => Some('5'),
+        'r' => Some('6'),
+        _ => None,
+    }
+}
+
+/// Code Soundex (règles des National Archives) : première lettre + 3 chiffres ("Robert" -> "R163").
+/// Deux lettres de même chiffre séparées par h ou w ne comptent qu'une fois, séparées
+/// par une voyelle elles comptent deux fois. `word` doit être ASCII alphabétique non vide.
+fn soundex(word: &str) -> String {
+    let mut chars = word.chars();
+    let Some(first) = chars.next() else {
+        return String::new();
+    };
+
+    let mut code = first.to_ascii_uppercase().to_string();
+    let mut previous = soundex_digit(first);
+    for c in chars {
+        if code.len() == 4 {
+            break;
+        }
+        let digit = soundex_digit(c);
+        if let Some(d) = digit
+            && digit != previous
+        {
+            code.push(d);
+        }
+        // h et w ne séparent pas deux lettres de même chiffre
+        if !matches!(c.to_ascii_lowercase(), 'h' | 'w') {
+            previous = digit;
+        }
+    }
+
+    while code.len() < 4 {
+        code.push('0');
+    }
+    code
+}
+
+/// Groupes d'au moins deux mots de même code, par fréquence cumulée décroissante
+fn print_phonetic_groups(counts: &HashMap<String, usize>) {
+    let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+    for word in counts.keys().filter(|w| !w.is_empty() && w.chars().all(|c| c.is_ascii_alphabetic())) {
+        groups.entry(soundex(word)).or_default().push(word.clone());
+    }
+
+    let mut groups: Vec<(String, Vec<String>, usize)> = groups
+        .into_iter()
+        .filter(|(_, words)| words.len() >= 2)
+        .map(|(code, mut words)| {
+            words.sort();
+            let total = words.iter().map(|w| counts[w]).sum();
+            (code, words, total)
+        })
+        .collect();
+    groups.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+
+    println!("\nPhonetic groups:");
+    if groups.is_empty() {
+        println!("No similar-sounding words found.");
+    }
+    for (code, words, _) in groups {
+        println!("{}: {}", code, words.join(", "));
+    }
+}
+
+// ==========================================
+// LOI DE ZIPF (--zipf)
+// ==========================================
+
+// Hauteur du nuage de points, en lignes
+const ZIPF_PLOT_HEIGHT: usize = 20;
+
+/// Pente de la régression linéaire de log10(count) sur log10(rang).
+/// `counts` doit être trié par fréquence décroissante (rang 1 en premier) ;
+/// NaN s'il y a moins de deux rangs.
+fn zipf_slope(counts: &[usize]) -> f64 {
+    let points: Vec<(f64, f64)> = counts
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| (((i + 1) as f64).log10(), (count as f64).log10()))
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    covariance / variance
+}
+
+/// Nuage de points log-log (rang en abscisse, fréquence en ordonnée) puis pente ajustée
+fn print_zipf(counts: &[usize], width: usize) {
+    println!("\nZipf plot (log10 rank vs log10 frequency):");
+    if counts.is_empty() {
+        println!("No words to plot.");
+        return;
+    }
+
+    // Marge de 8 colonnes pour l'axe des ordonnées
+    let cols = width.saturating_sub(8).max(10);
+    let max_x = (counts.len() as f64).log10().max(f64::EPSILON);
+    let max_y = (counts[0] as f64).log10().max(f64::EPSILON);
+
+    let mut plot = vec![vec![' '; cols]; ZIPF_PLOT_HEIGHT];
+    for (i, &count) in counts.iter().enumerate() {
+        let x = (((i + 1) as f64).log10() / max_x * (cols - 1) as f64).round() as usize;
+        let y = ((count as f64).log10() / max_y * (ZIPF_PLOT_HEIGHT - 1) as f64).round() as usize;
+        plot[ZIPF_PLOT_HEIGHT - 1 - y][x] = '*';
+    }
+
+    for (row, line) in plot.iter().enumerate() {
+        let label = match row {
+            0 => format!("{:>6}", counts[0]),
+            r if r == ZIPF_PLOT_HEIGHT - 1 => format!("{:>6}", 1),
+            _ => " ".repeat(6),
+        };
+        println!("{} |{}", label, line.iter().collect::<String>().trim_end());
+    }
+    println!("{} +{}", " ".repeat(6), "-".repeat(cols));
+    println!("{} 1{:>width$}", " ".repeat(7), counts.len(), width = cols - 1);
+
+    let slope = zipf_slope(counts);
+    if slope.is_finite() {
+        println!("Best-fit slope: {:.3}", slope);
+        let fits = (-1.3..=-0.7).contains(&slope);
+        println!("Fits Zipf: {}", if fits { "YES" } else { "NO" });
+    } else {
+        println!("Best-fit slope: n/a (need at least two distinct words)");
+    }
+}
+
+// ==========================================
+// COMPARAISON DE FRÉQUENCES (--diff)
+// ==========================================
+
+/// Texte de comparaison : contenu du fichier si le chemin existe, sinon l'argument lui-même
+fn read_other(other: &str) -> io::Result<String> {
+    if Path::new(other).is_file() { fs::read_to_string(other) } else { Ok(other.to_string()) }
+}
+
+/// Écart de fréquence b - a pour chaque mot présent d'un côté ou de l'autre
+/// (écarts nuls omis), trié par écart absolu décroissant puis alphabétiquement
+fn freq_diff(a: &HashMap<String, usize>, b: &HashMap<String, usize>) -> Vec<(String, isize)> {
+    let words: HashSet<&String> = a.keys().chain(b.keys()).collect();
+    let mut deltas: Vec<(String, isize)> = words
+        .into_iter()
+        .map(|word| {
+            let count = |map: &HashMap<String, usize>| map.get(word).copied().unwrap_or(0) as isize;
+            (word.clone(), count(b) - count(a))
+        })
+        .filter(|&(_, delta)| delta != 0)
+        .collect();
+    deltas.sort_by(|x, y| y.1.abs().cmp(&x.1.abs()).then_with(|| x.0.cmp(&y.0)));
+    deltas
+}
+
+/// Trois colonnes : en hausse, en baisse, présents d'un seul côté (N premiers de chaque)
+fn print_freq_diff(deltas: &[(String, isize)], a: &WordCounter, b: &WordCounter, top: usize) {
+    let mut increased = Vec::new();
+    let mut decreased = Vec::new();
+    let mut only_one = Vec::new();
+    for (word, delta) in deltas {
+        if !a.contains(word) {
+            only_one.push(format!("{} {:+} (other)", word, delta));
+        } else if !b.contains(word) {
+            only_one.push(format!("{} {:+} (input)", word, delta));
+        } else if *delta > 0 {
+            increased.push(format!("{} {:+}", word, delta));
+        } else {
+            decreased.push(format!("{} {:+}", word, delta));
+        }
+    }
+    let columns = [increased, decreased, only_one].map(|mut col| {
+        col.truncate(top);
+        col
+    });
+
+    let headers = ["Increased", "Decreased", "Only in one source"];
+    let widths: Vec<usize> = columns
+        .iter()
+        .zip(headers)
+        .map(|(col, header)| col.iter().map(|e| e.chars().count()).chain([header.len()]).max().unwrap_or(0))
+        .collect();
+
+    println!("Frequency diff (input -> other):");
+    println!("{:<w0$} | {:<w1$} | {}", headers[0], headers[1], headers[2], w0 = widths[0], w1 = widths[1]);
+    println!("{}-+-{}-+-{}", "-".repeat(widths[0]), "-".repeat(widths[1]), "-".repeat(widths[2]));
+
+    let rows = columns.iter().map(Vec::len).max().unwrap_or(0);
+    if rows == 0 {
+        println!("(no difference)");
+    }
+    for i in 0..rows {
+        let cell = |col: usize| columns[col].get(i).map_or("", String::as_str);
+        println!("{:<w0$} | {:<w1$} | {}", cell(0), cell(1), cell(2), w0 = widths[0], w1 = widths[1]);
+    }
+}
+
+// ==========================================
+// NUAGE DE MOTS (--word-cloud)
+// ==========================================
+
+// Essais de placement aléatoire avant d'abandonner un mot
+const CLOUD_MAX_ATTEMPTS: usize = 100;
+
+/// Grille de caractères où les mots sont posés sans se chevaucher
+struct WordCloud {
+    width: usize,
+    height: usize,
+    cells: Vec<Vec<char>>,
+    rng: SmallRng,
+}
+
+impl WordCloud {
+    fn new(width: usize, height: usize, seed: u64) -> Self {
+        WordCloud {
+            width,
+            height,
+            cells: vec![vec![' '; width]; height],
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Pose le mot à une position tirée au hasard, mis en forme selon son poids (0..1] :
+    /// lettres espacées en majuscules pour les plus fréquents, majuscules, puis minuscules.
+    /// Retourne false si aucune place libre n'a été trouvée.
+    fn place(&mut self, word: &str, weight: f32) -> bool {
+        let text: Vec<char> = if weight >= 0.66 {
+            let upper = word.to_uppercase();
+            upper.chars().flat_map(|c| [c, ' ']).take(upper.chars().count() * 2 - 1).collect()
+        } else if weight >= 0.33 {
+            word.to_uppercase().chars().collect()
+        } else {
+            word.to_lowercase().chars().collect()
+        };
+        if text.is_empty() || text.len() > self.width || self.height == 0 {
+            return false;
+        }
+
+        for _ in 0..CLOUD_MAX_ATTEMPTS {
+            let x = self.rng.random_range(0..=self.width - text.len());
+            let y = self.rng.random_range(0..self.height);
+            if self.is_free(x, y, text.len()) {
+                self.cells[y][x..x + text.len()].copy_from_slice(&text);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Zone libre, avec une colonne vide de chaque côté pour séparer les mots
+    fn is_free(&self, x: usize, y: usize, len: usize) -> bool {
+        let start = x.saturating_sub(1);
+        let end = (x + len + 1).min(self.width);
+        self.cells[y][start..end].iter().all(|&c| c == ' ')
+    }
+
+    fn render(&self) -> String {
+        let border = format!("+{}+", "-".repeat(self.width));
+        let mut out = border.clone();
+        for row in &self.cells {
+            out.push_str("\n|");
+            out.extend(row.iter());
+            out.push('|');
+        }
+        out.push('\n');
+        out.push_str(&border);
+        out
+    }
+}
+
+fn print_word_cloud(entries: &[(&String, &usize)], width: usize, seed: Option<u64>) {
+    // Sans --seed, on tire une graine et on l'affiche pour pouvoir rejouer le nuage
+    let seed = seed.unwrap_or_else(|| {
+        let seed = rand::rng().random();
+        eprintln!("Seed: {} (use --seed {} to reproduce)", seed, seed);
+        seed
+    });
+
+    let mut cloud = WordCloud::new(width, (width / 2).max(1), seed);
+    let max_count = entries.first().map(|&(_, &count)| count).unwrap_or(1);
+
+    // Les mots les plus fréquents sont placés en premier, quand la grille est encore vide
+    let skipped: Vec<&str> = entries
+        .iter()
+        .filter(|&&(word, &count)| !cloud.place(word, count as f32 / max_count as f32))
+        .map(|&(word, _)| word.as_str())
+        .collect();
+
+    println!("{}", cloud.render());
+    if !skipped.is_empty() {
+        println!("No room for: {}", skipped.join(", "));
+    }
+}
+
+/// Barre de longueur proportionnelle à count (max_count => bar_width caractères)
+fn render_bar(count: usize, max_count: usize, bar_width: usize) -> String {
+    if max_count == 0 {
+        return String::new();
+    }
+    let len = (count as f64 * bar_width as f64 / max_count as f64).round() as usize;
+    "█".repeat(len.min(bar_width))
+}
+
+/// Mode --backend trie : comptage, top N et autocomplétion répondus par le trie lui-même
+fn run_trie(content: &str, tokenizer: &Tokenizer, args: &Args) -> io::Result<()> {
+    let trie = count_trie(content, tokenizer, args)?;
+    let top = trie.top_n(args.top);
+    let entries: Vec<(&String, &usize)> = top.iter().map(|(word, count)| (word, count)).collect();
+    print_entries(&entries, trie.len(), args)?;
+
+    if args.sentences {
+        print_sentence_stats(content);
+    }
+    if args.readability {
+        print_readability(content);
+    }
+    if let Some(prefix) = &args.autocomplete {
+        let prefix = if args.ignore_case { prefix.to_lowercase() } else { prefix.clone() };
+        print_completions(trie.completions(&prefix), &prefix, args.autocomplete_limit);
+    }
+
+    Ok(())
+}
+
+/// Mode multi-fichiers : tableaux par fichier, agrégat, et comparaison optionnelle
+fn run_files(args: &Args, tokenizer: &Tokenizer) -> io::Result<()> {
+    // Expansion des motifs glob (un chemin simple est un motif valide)
+    let mut paths: Vec<String> = Vec::new();
+    for pattern in &args.files {
+        let entries = match glob::glob(pattern) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("Invalid glob pattern '{}': {}", pattern, e);
+                process::exit(1);
+            }
+        };
+        for entry in entries.flatten() {
+            if entry.is_file() {
+                paths.push(entry.to_string_lossy().into_owned());
+            }
+        }
+    }
+
+    if paths.is_empty() {
+        eprintln!("No files matched: {}", args.files.join(", "));
+        process::exit(1);
+    }
+
+    // Mode caractères : tous les fichiers alimentent le même compteur
+    if args.chars {
+        let mut counter = CharCounter::new(args.ignore_case, args.include_whitespace);
+        for path in &paths {
+            for line in fs::read_to_string(path)?.split_inclusive('\n') {
+                counter.feed_line(line);
+            }
+        }
+        return print_char_frequency(&counter, args);
+    }
+
+    let mut multi = MultiFileCounter::new();
+    let mut all_content = String::new();
+    for path in &paths {
+        let content = fs::read_to_string(path)?;
+        multi.add_file(path.clone(), count_content(&content, tokenizer, args)?);
+        all_content.push_str(&content);
+        all_content.push('\n');
+    }
+
+    // Tableaux par fichier (tous les mots), uniquement en sortie texte
+    if !args.json {
+        for (name, counter) in multi.iter() {
+            println!("== {} ==", name);
+            print_frequency_limit(counter, args, usize::MAX)?;
+            println!();
+        }
+        println!("== Aggregate ({} files) ==", multi.len());
+    }
+
+    // Agrégat : seul tableau limité par --top
+    print_frequency(multi.merged(), args)?;
+
+    if args.compare_files {
+        print_unique_words(&multi);
+    }
+
+    if args.sentences {
+        print_sentence_stats(&all_content);
+    }
+    if args.readability {
+        print_readability(&all_content);
+    }
+
+    Ok(())
+}
+
+/// Mode arborescence : fichiers *.<extension> comptés en parallèle, résumé par fichier puis agrégat
+fn run_dir(dir: &str, args: &Args, tokenizer: &Tokenizer) -> io::Result<()> {
+    let mut paths: Vec<String> = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| {
+            entry.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case(args.extension.as_str()))
+        })
+        .map(|entry| entry.path().to_string_lossy().into_owned())
+        .collect();
+    paths.sort();
+
+    if paths.is_empty() {
+        eprintln!("No .{} files found under {}", args.extension, dir);
+        process::exit(1);
+    }
+
+    // Mode caractères : tous les fichiers alimentent le même compteur
+    if args.chars {
+        let mut counter = CharCounter::new(args.ignore_case, args.include_whitespace);
+        for path in &paths {
+            for line in fs::read_to_string(path)?.split_inclusive('\n') {
+                counter.feed_line(line);
+            }
+        }
+        return print_char_frequency(&counter, args);
+    }
+
+    // Un compteur par fichier, calculés en parallèle (ordre des chemins conservé)
+    // Le texte n'est gardé que pour --sentences / --readability
+    let keep_content = args.sentences || args.readability;
+    let per_file: Vec<(&String, WordCounter, String)> = paths
+        .par_iter()
+        .map(|path| {
+            let content = fs::read_to_string(path)?;
+            let counter = count_content(&content, tokenizer, args)?;
+            Ok((path, counter, if keep_content { content } else { String::new() }))
+        })
+        .collect::<io::Result<_>>()?;
+
+    if !args.json {
+        let name_width = per_file.iter().map(|(path, _, _)| path.len()).max().unwrap_or(0).max(4);
+        println!("{:<name_width$} {:>8} {:>8}", "File", "Words", "Unique");
+        for (path, counter, _) in &per_file {
+            let total: usize = counter.counts.values().sum();
+            println!("{:<name_width$} {:>8} {:>8}", path, total, counter.counts.len());
+        }
+        println!("\n== Aggregate ({} files under {}) ==", per_file.len(), Path::new(dir).display());
+    }
+
+    let all_content: String = per_file.iter().map(|(_, _, content)| content.as_str()).collect::<Vec<_>>().join("\n");
+
+    // Fusion des comptes par réduction parallèle
+    let merged = per_file
+        .into_par_iter()
+        .map(|(_, counter, _)| counter)
+        .reduce(WordCounter::new, |mut acc, counter| {
+            acc.merge(&counter);
+            acc
+        });
+    print_frequency(&merged, args)?;
+
+    if args.sentences {
+        print_sentence_stats(&all_content);
+    }
+    if args.readability {
+        print_readability(&all_content);
+    }
+
+    Ok(())
+}
+
+/// Affiche côte à côte les mots propres à chaque fichier
+fn print_unique_words(multi: &MultiFileCounter) {
+    let columns = multi.unique_words();
+    let width = columns
+        .iter()
+        .flat_map(|(name, words)| std::iter::once(name.len()).chain(words.iter().map(|w| w.len())))
+        .max()
+        .unwrap_or(0)
+        + 2;
+    let rows = columns.iter().map(|(_, words)| words.len()).max().unwrap_or(0);
+
+    println!("\nWords unique to each file:");
+    for (name, _) in &columns {
+        print!("{:<width$}", name, width = width);
+    }
+    println!();
+    for (_, _) in &columns {
+        print!("{:<width$}", "-".repeat(width - 2), width = width);
+    }
+    println!();
+    for row in 0..rows {
+        for (_, words) in &columns {
+            let word = words.get(row).map(|w| w.as_str()).unwrap_or("");
+            print!("{:<width$}", word, width = width);
+        }
+        println!();
+    }
+}
+
+/// Affichage du mode caractères (même format que les mots)
+fn print_char_frequency(counter: &CharCounter, args: &Args) -> io::Result<()> {
+    let top = counter.top_n(args.top);
+
+    if args.json {
+        let entries: Vec<CharEntry> = top
+            .iter()
+            .map(|&(c, count)| CharEntry { char: c.to_string(), count })
+            .collect();
+        let json = if args.json_pretty {
+            serde_json::to_string_pretty(&entries)
+        } else {
+            serde_json::to_string(&entries)
+        };
+        println!("{}", json.map_err(io::Error::other)?);
+        return Ok(());
+    }
+
+    if args.top < counter.len() {
+        println!("Top {} characters:", args.top);
+    } else {
+        println!("Character frequency:");
+    }
+    for (c, count) in top {
+        println!("{}: {}", display_char(c), count);
+    }
+    Ok(())
+}
+
+/// Affiche un caractère ASCII imprimable tel quel, sinon en notation U+XXXX
+fn display_char(c: char) -> String {
+    if c.is_ascii_graphic() {
+        c.to_string()
+    } else {
+        format!("U+{:04X}", c as u32)
+    }
+}
+
+/// Tokens conservés : longueur minimale (--min-length), puis exclusions optionnelles
+struct TokenFilter {
+    min_length: usize,
+    numbers: bool,
+    mixed: bool,
+    stop_words: Option<StopWordFilter>,
+}
+
+impl TokenFilter {
+    fn new(min_length: usize) -> Self {
+        TokenFilter { min_length, numbers: false, mixed: false, stop_words: None }
+    }
+
+    /// Filtre complet de la ligne de commande, mots vides de --lang compris
+    fn from_args(args: &Args) -> io::Result<Self> {
+        let mut filter = TokenFilter::new(args.min_length);
+        if args.exclude_numbers {
+            filter = filter.exclude_numbers();
+        }
+        if args.exclude_mixed {
+            filter = filter.exclude_mixed();
+        }
+        if args.stop_words || args.stop_words_file.is_some() {
+            let extra: Vec<String> = match &args.stop_words_file {
+                Some(path) => fs::read_to_string(path)?.lines().map(String::from).collect(),
+                None => Vec::new(),
+            };
+            filter = filter.stop_words(StopWordFilter::new(LanguageProfile::new(args.lang).stop_words, &extra));
+        }
+        Ok(filter)
+    }
+
+    /// Rejette les tokens uniquement numériques ("42", "2024")
+    fn exclude_numbers(mut self) -> Self {
+        self.numbers = true;
+        self
+    }
+
+    /// Rejette les tokens mêlant lettres et chiffres ("3rd", "C3PO")
+    fn exclude_mixed(mut self) -> Self {
+        self.mixed = true;
+        self
+    }
+
+    /// Rejette les mots vides (--stop-words, --stop-words-file)
+    fn stop_words(mut self, filter: StopWordFilter) -> Self {
+        self.stop_words = Some(filter);
+        self
+    }
+
+    fn accepts(&self, token: &str) -> bool {
+        if token.len() < self.min_length {
+            return false;
+        }
+        if self.numbers && token.chars().all(char::is_numeric) {
+            return false;
+        }
+        let mixed = token.chars().any(char::is_alphabetic) && token.chars().any(char::is_numeric);
+        if self.mixed && mixed {
+            return false;
+        }
+        !self.stop_words.as_ref().is_some_and(|filter| filter.is_stop(token))
+    }
+}
+
+/// Stratégie de découpage du texte en tokens
+enum Tokenizer {
+    /// Découpe sur tout ce qui n'est pas alphanumérique, en gardant les apostrophes internes
+    Default,
+    /// Extrait les correspondances d'une expression régulière (--pattern)
+    Regex(regex::Regex),
+}
+
+impl Tokenizer {
+    /// Découpe le texte en tokens en appliquant le filtre de tokens et la gestion de la casse
+    fn tokenize(&self, content: &str, profile: &LanguageProfile, filter: &TokenFilter, ignore_case: bool) -> Vec<String> {
+        let mut tokens = Vec::new();
+        self.for_each_token(content, profile, filter, ignore_case, |token| tokens.push(token.to_string()));
+        tokens
+    }
+
+    /// Comme tokenize, mais passe chaque token à `f` sans l'allouer : tranche du texte,
+    /// ou tampon réutilisé pour la version en minuscules
+    fn for_each_token(
+        &self,
+        content: &str,
+        profile: &LanguageProfile,
+        filter: &TokenFilter,
+        ignore_case: bool,
+        mut f: impl FnMut(&str),
+    ) {
+        let raw: Box<dyn Iterator<Item = &str>> = match self {
+            // On découpe par tout ce qui n'est pas alphanumérique (pour virer la ponctuation),
+            // sauf les apostrophes internes : "don't" reste un mot, "l'homme" passe par
+            // split_contractions. Celles des bords sont des guillemets ('mot').
+            Tokenizer::Default => Box::new(
+                content
+                    .split(|c: char| !c.is_alphanumeric() && !is_apostrophe(c))
+                    .map(|token| token.trim_matches(is_apostrophe)),
+            ),
+            Tokenizer::Regex(re) => Box::new(re.find_iter(content).map(|m| m.as_str())),
+        };
+
+        let mut lower = String::new();
+        let mut emit = |token: &str| {
+            // Filtre de longueur, nombres, mots mixtes, mots vides
+            if token.is_empty() || !filter.accepts(token) {
+                return;
+            }
+            // Gestion de la casse
+            if ignore_case {
+                lower.clear();
+                if token.is_ascii() {
+                    lower.push_str(token);
+                    lower.make_ascii_lowercase();
+                } else {
+                    // Cas général (sigma final, etc.)
+                    lower.push_str(&token.to_lowercase());
+                }
+                f(&lower);
+            } else {
+                f(token);
+            }
+        };
+
+        for token in raw {
+            // Élisions (l'homme -> l, homme) selon la langue
+            if profile.contractions {
+                profile.split_contractions(token).into_iter().for_each(&mut emit);
+            } else {
+                emit(token);
+            }
+        }
+    }
+}
+
+/// Génère toutes les séquences de n tokens consécutifs
+fn ngrams(tokens: &[String], n: usize) -> Vec<Vec<String>> {
+    if n == 0 {
+        return Vec::new();
+    }
+    tokens.windows(n).map(|w| w.to_vec()).collect()
+}
+
+/// Découpe le texte en phrases sur les séquences de '.', '!' et '?'
+/// Une séquence comme "..." ou "?!" ne compte qu'une fois, et les guillemets
+/// fermants qui suivent la ponctuation restent attachés à la phrase.
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if !matches!(c, '.' | '!' | '?') {
+            continue;
+        }
+
+        // On absorbe toute la séquence de ponctuation et les guillemets fermants
+        let mut end = i + c.len_utf8();
+        while let Some(&(j, next)) = chars.peek() {
+            if matches!(next, '.' | '!' | '?' | '"' | '\'' | '”' | '’' | '»' | ')') {
+                end = j + next.len_utf8();
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        let sentence = text[start..end].trim();
+        if !sentence.is_empty() {
+            sentences.push(sentence);
+        }
+        start = end;
+    }
+
+    // Dernière phrase sans ponctuation finale
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+
+    sentences
+}
+
+/// Nombre de mots d'une phrase (même découpage que le comptage de mots)
+fn sentence_word_count(sentence: &str) -> usize {
+    sentence
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .count()
+}
+
+/// Affiche le nombre de phrases, la longueur moyenne et les phrases extrêmes
+fn print_sentence_stats(content: &str) {
+    // On ignore les "phrases" sans aucun mot (ex: "..." isolé)
+    let sentences: Vec<(&str, usize)> = split_sentences(content)
+        .into_iter()
+        .map(|s| (s, sentence_word_count(s)))
+        .filter(|&(_, words)| words > 0)
+        .collect();
+
+    println!("\nSentence statistics:");
+    println!("Sentences: {}", sentences.len());
+    if sentences.is_empty() {
+        return;
+    }
+
+    let total_words: usize = sentences.iter().map(|&(_, words)| words).sum();
+    println!("Average length: {:.2} words", total_words as f64 / sentences.len() as f64);
+
+    // max_by_key/min_by_key gardent respectivement la dernière/première égalité :
+    // on inverse l'itération pour garder la première phrase dans les deux cas
+    if let Some((sentence, words)) = sentences.iter().rev().max_by_key(|&&(_, words)| words) {
+        println!("Longest: {} words - \"{}\"", words, sentence);
+    }
+    if let Some((sentence, words)) = sentences.iter().min_by_key(|&&(_, words)| words) {
+        println!("Shortest: {} words - \"{}\"", words, sentence);
+    }
+}
+
+/// Place d'un mot dans sa phrase (index dans le tableau de position_counts)
+#[derive(Debug, Clone, Copy)]
+enum Position {
+    First,
+    Middle,
+    Last,
+}
+
+/// [premier, milieu, dernier] par mot ; une phrase d'un seul mot compte comme First
+fn position_counts(content: &str, tokenizer: &Tokenizer, args: &Args) -> io::Result<HashMap<String, [usize; 3]>> {
+    let filter = TokenFilter::from_args(args)?;
+    let profile = LanguageProfile::new(args.lang);
+    let mut counts: HashMap<String, [usize; 3]> = HashMap::new();
+    for sentence in split_sentences(content) {
+        let tokens = tokenizer.tokenize(sentence, &profile, &filter, args.ignore_case);
+        let last = tokens.len().saturating_sub(1);
+        for (i, token) in tokens.into_iter().enumerate() {
+            let position = match i {
+                0 => Position::First,
+                i if i == last => Position::Last,
+                _ => Position::Middle,
+            };
+            counts.entry(token).or_insert([0; 3])[position as usize] += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// Tableau word | first | middle | last, trié par total décroissant puis alphabétiquement
+fn print_position_analysis(counts: &HashMap<String, [usize; 3]>, top: usize) {
+    let mut rows: Vec<(&String, &[usize; 3])> = counts.iter().collect();
+    rows.sort_by(|a, b| {
+        let total = |c: &[usize; 3]| c.iter().sum::<usize>();
+        total(b.1).cmp(&total(a.1)).then_with(|| a.0.cmp(b.0))
+    });
+    rows.truncate(top);
+
+    let width = rows.iter().map(|(word, _)| word.chars().count()).max().unwrap_or(0).max(4);
+    println!("Sentence positions:");
+    println!("{:<width$} | first | middle | last", "word");
+    println!("{}-+-------+--------+-----", "-".repeat(width));
+    for (word, [first, middle, last]) in rows {
+        println!("{:<width$} | {:>5} | {:>6} | {:>4}", word, first, middle, last);
+    }
+}
+
+/// Poids TF-IDF des mots d'une phrase : TF = occurrences dans la phrase,
+/// IDF = ln(total_sentences / phrases contenant le mot)
+fn tfidf_score(
+    word_counts: &HashMap<String, usize>,
+    sentence_counts: &HashMap<String, usize>,
+    total_sentences: usize,
+) -> HashMap<String, f64> {
+    word_counts
+        .iter()
+        .map(|(word, &tf)| {
+            let df = sentence_counts.get(word).copied().unwrap_or(1).max(1);
+            let idf = (total_sentences as f64 / df as f64).ln();
+            (word.clone(), tf as f64 * idf)
+        })
+        .collect()
+}
+
+/// Note chaque phrase (somme des TF-IDF de ses mots) et affiche les N meilleures
+/// dans l'ordre du texte
+fn print_summary(content: &str, tokenizer: &Tokenizer, args: &Args, n: usize) -> io::Result<()> {
+    let filter = TokenFilter::from_args(args)?;
+    let profile = LanguageProfile::new(args.lang);
+    let sentences: Vec<(&str, HashMap<String, usize>)> = split_sentences(content)
+        .into_iter()
+        .map(|sentence| {
+            let mut counts = HashMap::new();
+            for token in tokenizer.tokenize(sentence, &profile, &filter, args.ignore_case) {
+                *counts.entry(token).or_insert(0) += 1;
+            }
+            (sentence, counts)
+        })
+        .filter(|(_, counts)| !counts.is_empty())
+        .collect();
+
+    // Nombre de phrases contenant chaque mot
+    let mut sentence_counts: HashMap<String, usize> = HashMap::new();
+    for (_, counts) in &sentences {
+        for word in counts.keys() {
+            *sentence_counts.entry(word.clone()).or_insert(0) += 1;
+        }
+    }
+
+    let mut ranked: Vec<(usize, f64)> = sentences
+        .iter()
+        .enumerate()
+        .map(|(i, (_, counts))| (i, tfidf_score(counts, &sentence_counts, sentences.len()).values().sum()))
+        .collect();
+    // Score décroissant, la première phrase gagne en cas d'égalité
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(n);
+    ranked.sort_by_key(|&(i, _)| i);
+
+    println!("Summary ({} of {} sentences):", ranked.len(), sentences.len());
+    for (i, _) in ranked {
+        println!("{}", sentences[i].0);
+    }
+    Ok(())
+}
+
+/// Compte naïvement les syllabes d'un mot anglais (groupes de voyelles)
+fn count_syllables(word: &str) -> usize {
+    let word = word.to_lowercase();
+    let chars: Vec<char> = word.chars().collect();
+    let is_vowel = |c: char| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+
+    let mut count = 0;
+    let mut prev_vowel = false;
+    for &c in &chars {
+        let vowel = is_vowel(c);
+        if vowel && !prev_vowel {
+            count += 1;
+        }
+        prev_vowel = vowel;
+    }
+
+    // E muet final ("make"), sauf terminaison "le" après une consonne ("table")
+    let n = chars.len();
+    if word.ends_with('e') && count > 1 {
+        let le_ending = word.ends_with("le") && n > 2 && !is_vowel(chars[n - 3]);
+        if !le_ending {
+            count -= 1;
+        }
+    }
+
+    // Au moins une syllabe par mot
+    count.max(1)
+}
+
+/// Affiche le Flesch Reading Ease et le Flesch-Kincaid Grade Level
+fn print_readability(content: &str) {
+    // Même découpage en phrases que --sentences
+    let sentence_count = split_sentences(content)
+        .into_iter()
+        .filter(|s| sentence_word_count(s) > 0)
+        .count();
+
+    // Les syllabes ne sont comptées que sur les mots alphabétiques (pas les nombres)
+    let words: Vec<&str> = content
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty() && s.chars().all(char::is_alphabetic))
+        .collect();
+
+    println!("\nReadability:");
+    if sentence_count == 0 || words.is_empty() {
+        println!("Not enough text to compute readability scores.");
+        return;
+    }
+
+    let syllables: usize = words.iter().map(|w| count_syllables(w)).sum();
+    let words_per_sentence = words.len() as f64 / sentence_count as f64;
+    let syllables_per_word = syllables as f64 / words.len() as f64;
+
+    let reading_ease = 206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word;
+    let grade_level = 0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59;
+
+    let ease_label = match reading_ease {
+        r if r >= 90.0 => "Very easy",
+        r if r >= 70.0 => "Easy",
+        r if r >= 60.0 => "Standard",
+        r if r >= 50.0 => "Fairly difficult",
+        r if r >= 30.0 => "Difficult",
+        _ => "Very difficult",
+    };
+    let grade_label = match grade_level {
+        g if g > 13.0 => "College-level",
+        g if g > 8.0 => "High school",
+        g if g > 5.0 => "Middle school",
+        _ => "Elementary school",
+    };
+
+    println!("Flesch Reading Ease: {:.2} ({})", reading_ease, ease_label);
+    println!("Flesch-Kincaid Grade Level: {:.2} ({})", grade_level, grade_label);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        text.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn ngrams_of_known_sentence() {
+        let tokens = words("the cat sat on the mat");
+        assert_eq!(ngrams(&tokens, 1).len(), 6);
+        assert_eq!(
+            ngrams(&tokens, 2),
+            vec![
+                words("the cat"),
+                words("cat sat"),
+                words("sat on"),
+                words("on the"),
+                words("the mat"),
+            ]
+        );
+        assert_eq!(ngrams(&tokens, 3), vec![words("the cat sat"), words("cat sat on"), words("sat on the"), words("on the mat")]);
+    }
+
+    #[test]
+    fn ngrams_longer_than_text() {
+        assert!(ngrams(&words("one two"), 3).is_empty());
+        assert!(ngrams(&words("one two"), 0).is_empty());
+    }
+
+    #[test]
+    fn stop_words_filtered_from_paragraph() {
+        let paragraph = "The history of the town and the river is one of trade and of war.";
+        let filter = StopWordFilter::new(ENGLISH_STOP_WORDS, &[]);
+        let kept: Vec<&str> = paragraph
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty() && !filter.is_stop(w))
+            .collect();
+        assert_eq!(kept, vec!["history", "town", "river", "trade", "war"]);
+        assert!(filter.is_stop("The") && filter.is_stop("AND") && filter.is_stop("of"));
+    }
+
+    #[test]
+    fn stop_words_extra_list_is_merged() {
+        let filter = StopWordFilter::new(ENGLISH_STOP_WORDS, &[String::from(" River ")]);
+        assert!(filter.is_stop("river"));
+        assert!(filter.is_stop("the"));
+        assert!(!filter.is_stop("town"));
+    }
+
+    #[test]
+    fn render_bar_known_values() {
+        assert_eq!(render_bar(213, 213, 40), "█".repeat(40));
+        assert_eq!(render_bar(50, 100, 40), "█".repeat(20));
+        assert_eq!(render_bar(1, 3, 10), "█".repeat(3));
+        assert_eq!(render_bar(2, 3, 10), "█".repeat(7));
+        assert_eq!(render_bar(0, 100, 40), "");
+        assert_eq!(render_bar(5, 0, 40), "");
+    }
+
+    #[test]
+    fn decode_hex_stream_hello() {
+        assert_eq!(decode_hex_stream("48656c6c6f"), Ok(String::from("Hello")));
+        assert_eq!(decode_hex_stream("48 65\n6C 6C 6F"), Ok(String::from("Hello")));
+    }
+
+    #[test]
+    fn decode_hex_stream_invalid() {
+        assert!(decode_hex_stream("486").is_err());
+        assert!(decode_hex_stream("4g").is_err());
+        assert_eq!(decode_hex_stream("48ff"), Ok(String::from("H\u{FFFD}")));
+    }
+
+    #[test]
+    fn token_filter_applies_stop_words() {
+        let filter = TokenFilter::new(1).stop_words(StopWordFilter::new(FRENCH_STOP_WORDS, &[]));
+        let tokens = Tokenizer::Default.tokenize("Le chat de l'homme", &LanguageProfile::new(Lang::Fr), &filter, true);
+        assert_eq!(tokens, vec!["chat", "homme"]);
+    }
+
+    #[test]
+    fn trie_counter_top_n_matches_hash_order() {
+        let mut trie = TrieCounter::new();
+        for word in "b a c a b a d the then the".split(' ') {
+            trie.insert(word);
+        }
+        assert_eq!(trie.len(), 6);
+        let expected = [("a", 3), ("b", 2), ("the", 2)].map(|(w, c)| (w.to_string(), c));
+        assert_eq!(trie.top_n(3), expected);
+        assert!(trie.top_n(0).is_empty());
+        assert_eq!(trie.top_n(usize::MAX).len(), 6);
+    }
+
+    #[test]
+    fn default_tokenizer_splits_french_elisions() {
+        let filter = TokenFilter::new(1);
+        let fr = Tokenizer::Default.tokenize("L'homme", &LanguageProfile::new(Lang::Fr), &filter, true);
+        assert_eq!(fr, ["l", "homme"]);
+        // En anglais l'apostrophe reste dans le mot, les guillemets simples sont retirés
+        let en = Tokenizer::Default.tokenize("don't 'quote'", &LanguageProfile::new(Lang::En), &filter, true);
+        assert_eq!(en, ["don't", "quote"]);
+    }
+}
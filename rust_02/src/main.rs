@@ -1,15 +1,39 @@
-use clap::Parser;
-use std::fs::OpenOptions;
-use std::io::{self, Read, Seek, SeekFrom, Write};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{Parser, ValueEnum};
+use std::cell::Cell;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufReader, BufWriter, IsTerminal, Read, Seek, SeekFrom, Write};
+use std::path::Path;
 use std::process;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use image::{Rgb, RgbImage};
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
 
 /// Read and write binary files in hexadecimal
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
     /// Target file
-    #[arg(short, long)]
-    file: String,
+    #[arg(short, long, required_unless_present = "hex_stdin", conflicts_with = "hex_stdin")]
+    file: Option<String>,
+
+    /// Read hex bytes from stdin into a temporary file and use it as the target
+    #[arg(long, conflicts_with_all = ["interactive", "undo", "redo", "diff", "watch"])]
+    hex_stdin: bool,
 
     /// Read mode (display hex)
     #[arg(short, long, group = "action")]
@@ -26,143 +50,2486 @@ struct Args {
     /// Number of bytes to read
     #[arg(short, long)]
     size: Option<u64>,
+
+    /// Insert mode (hex string inserted at --offset, following bytes shifted right)
+    #[arg(long, group = "action")]
+    insert: Option<String>,
+
+    /// Delete mode (removes --size bytes at --offset, following bytes shifted left)
+    #[arg(long, group = "action", requires = "size")]
+    delete: bool,
+
+    /// Find mode (hex pattern to search for)
+    #[arg(long, group = "action")]
+    find: Option<String>,
+
+    /// Report every match instead of only the first (with --find)
+    #[arg(long, requires = "find")]
+    find_all: bool,
+
+    /// Fill mode (hex pattern repeated over --size bytes from --offset)
+    #[arg(long, group = "action")]
+    fill: Option<String>,
+
+    /// Allow --fill/--import-b64 to grow the file past its end
+    #[arg(long)]
+    extend: bool,
+
+    /// Zero-extend the file to SIZE bytes (no-op if already larger)
+    #[arg(long, group = "action", value_name = "SIZE")]
+    resize: Option<u64>,
+
+    /// Shrink the file to SIZE bytes
+    #[arg(long, group = "action", value_name = "SIZE")]
+    truncate: Option<u64>,
+
+    /// Show what --write/--fill would do without modifying the file
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Byte display format
+    #[arg(long, value_enum, default_value_t = DisplayMode::Hex)]
+    display: DisplayMode,
+
+    /// Checksum mode (digest of the --offset/--size range, whole file by default)
+    #[arg(long, value_enum, group = "action")]
+    checksum: Option<ChecksumAlgo>,
+
+    /// Expected digest in hex: exit 0 if it matches, 2 otherwise (with --checksum)
+    #[arg(long, requires = "checksum")]
+    verify: Option<String>,
+
+    /// Strings mode (printable ASCII runs of at least N bytes, default 4)
+    #[arg(long, group = "action", num_args = 0..=1, default_missing_value = "4")]
+    strings: Option<usize>,
+
+    /// Also extract UTF-16LE strings (with --strings)
+    #[arg(long, requires = "strings")]
+    wide_strings: bool,
+
+    /// Entropy mode (Shannon entropy per 256-byte block)
+    #[arg(long, group = "action")]
+    entropy: bool,
+
+    /// Print an alert for blocks above this entropy (with --entropy)
+    #[arg(long, requires = "entropy")]
+    entropy_threshold: Option<f64>,
+
+    /// Interactive full-screen hex editor
+    #[arg(short, long, group = "action")]
+    interactive: bool,
+
+    /// Byte-frequency histogram of the --offset/--size range
+    #[arg(long, group = "action")]
+    byte_freq: bool,
+
+    /// Histogram order (with --byte-freq)
+    #[arg(long, value_enum, default_value_t = ByteFreqSort::Count, requires = "byte_freq")]
+    byte_freq_sort: ByteFreqSort,
+
+    /// Print the 3 most likely single-byte XOR keys (with --byte-freq)
+    #[arg(long, requires = "byte_freq")]
+    xor_hint: bool,
+
+    /// XOR mode (repeating hex key applied in place over --offset/--size)
+    #[arg(long, group = "action")]
+    xor: Option<String>,
+
+    /// Print the --offset/--size range encoded as base64
+    #[arg(long, group = "action")]
+    export_b64: bool,
+
+    /// Import mode (base64 string decoded and written at --offset)
+    #[arg(long, group = "action")]
+    import_b64: Option<String>,
+
+    /// Watch mode (redraws the --offset/--size hex dump whenever the file changes)
+    #[arg(long, group = "action")]
+    watch: bool,
+
+    /// Delay between two modification checks in --watch mode, in milliseconds
+    #[arg(long, value_name = "MS", default_value_t = 500, requires = "watch")]
+    poll_interval: u64,
+
+    /// Render the --offset/--size range as a PNG, one pixel per byte (dark blue = 00, bright red = FF)
+    #[arg(long, group = "action", value_name = "OUTPUT_PNG")]
+    map: Option<String>,
+
+    /// Image width in pixels for --map (the height follows from the data size)
+    #[arg(long, value_name = "PIXELS", default_value_t = 256, requires = "map",
+          value_parser = clap::value_parser!(u32).range(1..))]
+    map_width: u32,
+
+    /// Byte-swap every WIDTH-byte word (2, 4 or 8) of the --offset/--size range in place
+    #[arg(long, group = "action", value_name = "WIDTH",
+          value_parser = PossibleValuesParser::new(["2", "4", "8"]).map(|w| w.parse::<usize>().unwrap()))]
+    swap_endian: Option<usize>,
+
+    /// Reverse the byte order of the whole --offset/--size range in place (to end of file without --size)
+    #[arg(long, group = "action")]
+    reverse: bool,
+
+    /// Combine mode (other file merged byte by byte with --file into --output-file)
+    #[arg(long, group = "action", value_name = "OTHER_FILE", requires = "output_file")]
+    combine: Option<String>,
+
+    /// Byte operation used by --combine
+    #[arg(long, value_enum, default_value_t = CombineOp::Xor, requires = "combine")]
+    op: CombineOp,
+
+    /// Output file written by --combine
+    #[arg(long, requires = "combine")]
+    output_file: Option<String>,
+
+    /// Zero-pad the shorter file instead of stopping at its end (with --combine)
+    #[arg(long, requires = "combine")]
+    pad: bool,
+
+    /// Gzip the --offset/--size range and write the result back at --offset
+    #[arg(long, group = "action")]
+    compress: bool,
+
+    /// Gunzip the --offset/--size range and write the result back at --offset
+    #[arg(long, group = "action")]
+    decompress: bool,
+
+    /// Undo the last journaled --write/--fill/--xor/--import-b64/--insert/--delete/--patch/--compress/--decompress/--swap-endian/--reverse
+    #[arg(long, group = "action")]
+    undo: bool,
+
+    /// Re-apply the most recently undone operation
+    #[arg(long, group = "action")]
+    redo: bool,
+
+    /// Maximum number of entries kept in the undo journal
+    #[arg(long, default_value_t = 100)]
+    journal_limit: usize,
+
+    /// Diff mode (other file to compare against)
+    #[arg(long, group = "action")]
+    diff: Option<String>,
+
+    /// Patch mode (file of "<offset_hex> <hex_bytes>" lines, each applied as a --write)
+    #[arg(long, group = "action", value_name = "PATCHFILE")]
+    patch: Option<String>,
+
+    /// Abort --patch on the first malformed line or failed write instead of skipping it
+    #[arg(long, requires = "patch")]
+    strict: bool,
+
+    /// Split mode (file cut into N parts <file>.part0..<file>.partN-1, the last one may be smaller)
+    #[arg(long, group = "action", value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    split: Option<u64>,
+
+    /// Split mode with a fixed part size in bytes instead of a part count
+    #[arg(long, group = "action", value_name = "BYTES", value_parser = clap::value_parser!(u64).range(1..))]
+    split_size: Option<u64>,
+
+    /// Join mode (concatenates <file>.part0, <file>.part1... into OUTPUT)
+    #[arg(long, group = "action", value_name = "OUTPUT")]
+    join: Option<String>,
+
+    /// Number of identical lines shown around each difference (with --diff)
+    #[arg(long, default_value_t = 3, requires = "diff")]
+    context: usize,
+
+    /// Log file sizes, seeks, byte counts and OS error codes to stderr (--read/--write)
+    #[arg(long)]
+    verbose: bool,
+}
+
+/// Format d'affichage des octets
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum DisplayMode {
+    Hex,
+    Octal,
+    Binary,
+}
+
+impl DisplayMode {
+    /// Nombre d'octets par ligne du dump
+    fn bytes_per_line(self) -> usize {
+        match self {
+            DisplayMode::Hex => 16,
+            DisplayMode::Octal => 8,
+            DisplayMode::Binary => 4,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            DisplayMode::Hex => "Hex",
+            DisplayMode::Octal => "Octal",
+            DisplayMode::Binary => "Binary",
+        }
+    }
+}
+
+/// Ordre de l'histogramme --byte-freq
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum ByteFreqSort {
+    /// Fréquence décroissante
+    Count,
+    /// Valeur d'octet croissante
+    Value,
+}
+
+/// Opération octet par octet de --combine
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum CombineOp {
+    Xor,
+    And,
+    Or,
+    /// Addition modulo 256
+    Add,
+}
+
+impl CombineOp {
+    fn apply(self, a: u8, b: u8) -> u8 {
+        match self {
+            CombineOp::Xor => a ^ b,
+            CombineOp::And => a & b,
+            CombineOp::Or => a | b,
+            CombineOp::Add => a.wrapping_add(b),
+        }
+    }
+}
+
+/// Algorithme de somme de contrôle
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum ChecksumAlgo {
+    Md5,
+    Sha256,
+}
+
+/// Calcul d'empreinte : un implémenteur par algorithme
+trait Digest {
+    /// Nom affiché (style BSD : "MD5", "SHA256")
+    fn name(&self) -> &'static str;
+    fn digest(&self, data: &[u8]) -> Vec<u8>;
+}
+
+struct Md5Digest;
+
+impl Digest for Md5Digest {
+    fn name(&self) -> &'static str {
+        "MD5"
+    }
+
+    fn digest(&self, data: &[u8]) -> Vec<u8> {
+        md5::compute(data).0.to_vec()
+    }
+}
+
+struct Sha256Digest;
+
+impl Digest for Sha256Digest {
+    fn name(&self) -> &'static str {
+        "SHA256"
+    }
+
+    fn digest(&self, data: &[u8]) -> Vec<u8> {
+        use sha2::Digest as _;
+        sha2::Sha256::digest(data).to_vec()
+    }
+}
+
+impl ChecksumAlgo {
+    fn digester(self) -> Box<dyn Digest> {
+        match self {
+            ChecksumAlgo::Md5 => Box::new(Md5Digest),
+            ChecksumAlgo::Sha256 => Box::new(Sha256Digest),
+        }
+    }
+}
+
+/// Entrée du journal d'annulation (<file>.hexjournal, une ligne JSON par entrée)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JournalEntry {
+    offset: u64,
+    original_bytes_hex: String,
+    written_bytes_hex: String,
+    timestamp_unix: u64,
+    /// Taille du fichier avant l'écriture (pour annuler une extension)
+    #[serde(default)]
+    file_len_before: Option<u64>,
+    /// Nature de l'opération (absent dans les anciens journaux : écrasement)
+    #[serde(default)]
+    op: JournalOp,
+}
+
+/// Écrasement en place, ou modification de la taille du fichier par insertion/suppression
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum JournalOp {
+    #[default]
+    Overwrite,
+    /// `written_bytes_hex` insérés à `offset`
+    Insert,
+    /// `original_bytes_hex` retirés à `offset`
+    Delete,
+}
+
+/// Fichier concerné par une ligne présente d'un seul côté
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// Ligne de diff (16 octets) : offset + octets de chaque côté
+#[derive(Debug, PartialEq)]
+enum DiffLine {
+    Same(u64, Vec<u8>),
+    Different(u64, Vec<u8>, Vec<u8>),
+    OnlyIn(Side, u64, Vec<u8>),
+}
+
+impl DiffLine {
+    fn offset(&self) -> u64 {
+        match self {
+            DiffLine::Same(offset, _) => *offset,
+            DiffLine::Different(offset, _, _) => *offset,
+            DiffLine::OnlyIn(_, offset, _) => *offset,
+        }
+    }
+}
+
+// ==========================================
+// JOURNALISATION DÉTAILLÉE (--verbose)
+// ==========================================
+
+/// Diagnostics [VERB] sur stderr ; passé en Option, None coupe tout sans coût
+#[derive(Debug, Clone, Copy)]
+struct VerboseLogger;
+
+impl VerboseLogger {
+    fn log_file_size(&self, when: &str, len: u64) {
+        eprintln!("[VERB] file size {}: {} bytes", when, len);
+    }
+
+    fn log_seek(&self, offset: u64) {
+        eprintln!("[VERB] seek to {:#010x}", offset);
+    }
+
+    /// Une lecture courte signale la fin du fichier avant `requested`
+    fn log_read(&self, requested: u64, actual: usize) {
+        let short = if (actual as u64) < requested { " (short read: end of file)" } else { "" };
+        eprintln!("[VERB] read {} of {} requested bytes{}", actual, requested, short);
+    }
+
+    fn log_write(&self, bytes: usize) {
+        eprintln!("[VERB] wrote {} bytes", bytes);
+    }
+
+    /// Code errno de l'OS quand l'erreur vient d'un appel système
+    fn log_error(&self, e: &HexEditorError) {
+        if let HexEditorError::IoError(io_err) = e {
+            match io_err.raw_os_error() {
+                Some(code) => eprintln!("[VERB] OS error {} ({:?})", code, io_err.kind()),
+                None => eprintln!("[VERB] I/O error without OS code ({:?})", io_err.kind()),
+            }
+        }
+    }
+}
+
+// ==========================================
+// ERREURS
+// ==========================================
+
+/// Erreurs des opérations sur fichier, à filtrer par variante côté appelant
+#[derive(Debug)]
+#[non_exhaustive]
+enum HexEditorError {
+    /// Erreur d'entrée/sortie sur le fichier
+    IoError(io::Error),
+    /// Donnée mal formée (chaîne hex, journal)
+    ParseError(String),
+    /// Offset ni décimal ni hexadécimal (0x...)
+    InvalidOffset(String),
+    /// Option ou argument incohérent (motif vide, --size manquant...)
+    InvalidArgument(String),
+    /// Plage au-delà de la fin du fichier (sans --extend)
+    OutOfBounds { offset: u64, end: u64, file_len: u64 },
+    /// Journal vide
+    NothingToUndo,
+    NothingToRedo,
+}
+
+impl fmt::Display for HexEditorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HexEditorError::IoError(e) => write!(f, "{}", e),
+            HexEditorError::ParseError(msg) => write!(f, "{}", msg),
+            HexEditorError::InvalidOffset(input) if input.starts_with("0x") => {
+                write!(f, "Invalid hex offset: {}", input)
+            }
+            HexEditorError::InvalidOffset(input) => write!(f, "Invalid decimal offset: {}", input),
+            HexEditorError::InvalidArgument(msg) => write!(f, "{}", msg),
+            HexEditorError::OutOfBounds { offset, end, file_len } => write!(
+                f,
+                "range {:#010x}..{:#010x} exceeds file length ({} bytes), use --extend to grow the file",
+                offset, end, file_len
+            ),
+            HexEditorError::NothingToUndo => write!(f, "Nothing to undo"),
+            HexEditorError::NothingToRedo => write!(f, "Nothing to redo"),
+        }
+    }
+}
+
+impl std::error::Error for HexEditorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HexEditorError::IoError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for HexEditorError {
+    fn from(e: io::Error) -> Self {
+        HexEditorError::IoError(e)
+    }
 }
 
 fn main() {
     let args = Args::parse();
+    // 0. --hex-stdin : les octets décodés deviennent un fichier temporaire (supprimé au drop)
+    let temp = args.hex_stdin.then(|| {
+        let mut input = String::new();
+        if let Err(e) = io::stdin().read_to_string(&mut input) {
+            eprintln!("Error reading stdin: {}", e);
+            process::exit(1);
+        }
+        match hex_stdin_to_tempfile(&input) {
+            Ok(temp) => temp,
+            Err(e) => {
+                eprintln!("Error decoding hex from stdin: {}", e);
+                process::exit(1);
+            }
+        }
+    });
+    let initial = temp.as_ref().map(|t| fs::read(t.path()).unwrap_or_default());
+    // clap garantit --file hors --hex-stdin
+    let file = match &temp {
+        Some(t) => t.path().to_string_lossy().into_owned(),
+        None => args.file.clone().unwrap_or_default(),
+    };
+
+    // Le fichier temporaire est supprimé avant process::exit, qui ne lance pas les destructeurs ;
+    // on affiche aussi le résultat s'il a été modifié
+    let code = run(&args, &file, temp.is_some());
+    if let (Some(temp), Some(initial)) = (temp, initial) {
+        finish_hex_stdin(temp, &initial);
+    }
+    process::exit(code);
+}
+
+/// Exécute l'action demandée sur `file` et retourne le code de sortie du programme
+fn run(args: &Args, file: &str, from_stdin: bool) -> i32 {
+    let logger = args.verbose.then_some(VerboseLogger);
 
     // 1. Parsing de l'offset (décimal ou hexadécimal)
     let offset = match parse_offset(&args.offset) {
         Ok(o) => o,
         Err(e) => {
             eprintln!("Error parsing offset: {}", e);
-            process::exit(1);
+            return 1;
         }
     };
 
-    // 2. Mode Écriture (--write)
-    if let Some(hex_str) = args.write {
-        if let Err(e) = do_write(&args.file, offset, &hex_str) {
+    // 2. Mode Écriture (--write), journalisé pour --undo (sauf --dry-run)
+    if let Some(hex_str) = &args.write {
+        let len = hex_str.len() as u64 / 2;
+        let result = if args.dry_run {
+            do_write(file, offset, hex_str, args.display, true, logger)
+        } else {
+            with_journal(file, offset, len, args.journal_limit, || {
+                do_write(file, offset, hex_str, args.display, false, logger)
+            })
+        };
+        if let Err(e) = result {
+            if let Some(l) = &logger {
+                l.log_error(&e);
+            }
             eprintln!("Error writing file: {}", e);
-            process::exit(1);
+            return 1;
         }
     } 
-    // 3. Mode Lecture (--read ou défaut si rien spécifié mais logique clap group)
+    // 3. Mode Remplissage (--fill), journalisé pour --undo (sauf --dry-run)
+    else if let Some(hex_str) = &args.fill {
+        let result = match args.size {
+            Some(size) if args.dry_run => hex_string_to_bytes(hex_str)
+                .and_then(|pattern| do_fill(file, offset, size, &pattern, args.extend, args.display, true)),
+            Some(size) => hex_string_to_bytes(hex_str).and_then(|pattern| {
+                with_journal(file, offset, size, args.journal_limit, || {
+                    do_fill(file, offset, size, &pattern, args.extend, args.display, false)
+                })
+            }),
+            None => Err(HexEditorError::InvalidArgument("--fill requires --size".to_string())),
+        };
+        if let Err(e) = result {
+            eprintln!("Error filling file: {}", e);
+            return 1;
+        }
+    }
+    // 4. Mode Recherche (--find)
+    else if let Some(hex_str) = &args.find {
+        match do_find(file, offset, args.size, hex_str, args.find_all) {
+            // Comme grep : 0 si au moins une occurrence, 1 sinon
+            Ok(found) => return if found { 0 } else { 1 },
+            Err(e) => {
+                eprintln!("Error searching file: {}", e);
+                return 1;
+            }
+        }
+    }
+    // 5. Mode Somme de contrôle (--checksum)
+    else if let Some(algo) = args.checksum {
+        let Some(data) = read_range_or_report(file, offset, args.size) else {
+            return 1;
+        };
+        let digest = compute_checksum(&data, algo);
+        let label = if from_stdin { "stdin" } else { file };
+        println!("{} ({}): {}", algo.digester().name(), label, digest);
+
+        if let Some(expected) = &args.verify {
+            if digest.eq_ignore_ascii_case(expected.trim()) {
+                println!("✓ checksum verified");
+            } else {
+                println!("✗ checksum mismatch (expected {})", expected.trim());
+                return 2;
+            }
+        }
+    }
+    // 6. Mode Chaînes (--strings)
+    else if let Some(min_len) = args.strings {
+        let Some(data) = read_range_or_report(file, offset, args.size) else {
+            return 1;
+        };
+
+        let mut found: Vec<(u64, String, bool)> = extract_strings(&data, min_len)
+            .into_iter()
+            .map(|(pos, text)| (pos, text, false))
+            .collect();
+        if args.wide_strings {
+            found.extend(
+                extract_wide_strings(&data, min_len)
+                    .into_iter()
+                    .map(|(pos, text)| (pos, text, true)),
+            );
+            found.sort_by_key(|(pos, _, _)| *pos);
+        }
+
+        for (pos, text, wide) in &found {
+            let suffix = if *wide { " (UTF-16LE)" } else { "" };
+            println!("{:#010x}: \"{}\"{}", offset + pos, text, suffix);
+        }
+        // Comme grep : 0 si au moins une chaîne, 1 sinon
+        return if found.is_empty() { 1 } else { 0 };
+    }
+    // 7. Mode Entropie (--entropy)
+    else if args.entropy {
+        let Some(data) = read_range_or_report(file, offset, args.size) else {
+            return 1;
+        };
+        do_entropy(&data, offset, args.entropy_threshold);
+    }
+    // 8. Mode Diff (--diff)
+    else if let Some(other) = &args.diff {
+        match do_diff(file, other, args.context) {
+            // Comme diff/cmp : 0 si identiques, 1 sinon
+            Ok(identical) => return if identical { 0 } else { 1 },
+            Err(e) => {
+                eprintln!("Error comparing files: {}", e);
+                return 1;
+            }
+        }
+    }
+    // 9. Mode Interactif (--interactive)
+    else if args.interactive {
+        if let Err(e) = run_interactive(file) {
+            eprintln!("Error in interactive editor: {}", e);
+            return 1;
+        }
+    }
+    // 10. Annulation / rétablissement (--undo / --redo)
+    else if args.undo || args.redo {
+        let result = if args.undo {
+            do_undo(file, args.journal_limit)
+        } else {
+            do_redo(file, args.journal_limit)
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {}", e);
+            return 1;
+        }
+    }
+    // 11. Histogramme des octets (--byte-freq)
+    else if args.byte_freq {
+        let Some(data) = read_range_or_report(file, offset, args.size) else {
+            return 1;
+        };
+        do_byte_freq(&data, args.byte_freq_sort, args.xor_hint);
+    }
+    // 12. Mode XOR (--xor), journalisé pour --undo
+    else if let Some(key_hex) = &args.xor {
+        let file_len = fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+        let len = args.size.unwrap_or(file_len.saturating_sub(offset));
+        let result = hex_string_to_bytes(key_hex).and_then(|key| {
+            with_journal(file, offset, len, args.journal_limit, || {
+                do_xor(file, offset, args.size, &key)
+            })
+        });
+        if let Err(e) = result {
+            eprintln!("Error applying XOR: {}", e);
+            return 1;
+        }
+    }
+    // 13. Export base64 (--export-b64)
+    else if args.export_b64 {
+        let Some(data) = read_range_or_report(file, offset, args.size) else {
+            return 1;
+        };
+        println!("{}", BASE64.encode(&data));
+    }
+    // 14. Import base64 (--import-b64), journalisé pour --undo
+    else if let Some(b64) = &args.import_b64 {
+        let bytes = match BASE64.decode(b64.trim()) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                eprintln!("Invalid base64 input: {}", e);
+                return 1;
+            }
+        };
+        let result = with_journal(file, offset, bytes.len() as u64, args.journal_limit, || {
+            do_import_b64(file, offset, &bytes, args.extend, args.display)
+        });
+        if let Err(e) = result {
+            eprintln!("Error writing file: {}", e);
+            return 1;
+        }
+    }
+    // 15. Mode Insertion (--insert)
+    else if let Some(hex_str) = &args.insert {
+        let result = hex_string_to_bytes(hex_str)
+            .and_then(|bytes| do_insert(file, offset, &bytes, args.journal_limit));
+        if let Err(e) = result {
+            eprintln!("Error inserting bytes: {}", e);
+            return 1;
+        }
+    }
+    // 16. Mode Suppression (--delete)
+    else if args.delete {
+        let size = args.size.unwrap_or(0);
+        if let Err(e) = do_delete(file, offset, size, args.journal_limit) {
+            eprintln!("Error deleting bytes: {}", e);
+            return 1;
+        }
+    }
+    // 17. Redimensionnement (--truncate / --resize)
+    else if let Some((new_len, allow_shrink)) = args.truncate.map(|n| (n, true)).or(args.resize.map(|n| (n, false))) {
+        if let Err(e) = do_resize(file, new_len, allow_shrink) {
+            eprintln!("Error resizing file: {}", e);
+            return 1;
+        }
+    }
+    // 18. Mode Patch (--patch), chaque ligne journalisée comme un --write
+    else if let Some(patch_path) = &args.patch {
+        let patches = fs::read_to_string(patch_path)
+            .map_err(|e| format!("{}: {}", patch_path, e))
+            .and_then(|content| parse_patch_file(&content, args.strict));
+        let (patches, skipped) = match patches {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("Error reading patch file: {}", e);
+                return 1;
+            }
+        };
+        // Les lignes invalides comptent dans le total : elles n'ont pas été appliquées
+        let applied = do_patch(file, &patches, args, logger);
+        let total = patches.len() + skipped;
+        println!("{}{}/{} patches applied", dry_run_prefix(args.dry_run), applied, total);
+        if applied < total {
+            return 1;
+        }
+    }
+    // 19. Découpage (--split / --split-size) et réassemblage (--join)
+    else if let Some(n) = args.split {
+        if let Err(e) = do_split(file, n as usize) {
+            eprintln!("Error splitting file: {}", e);
+            return 1;
+        }
+    } else if let Some(part_size) = args.split_size {
+        if let Err(e) = split_by_size(file, part_size) {
+            eprintln!("Error splitting file: {}", e);
+            return 1;
+        }
+    } else if let Some(output) = &args.join {
+        if let Err(e) = do_join(file, output) {
+            eprintln!("Error joining parts: {}", e);
+            return 1;
+        }
+    }
+    // 20. Compression gzip en place (--compress / --decompress), journalisée pour --undo
+    else if args.compress || args.decompress {
+        if let Err(e) = do_gzip(file, offset, args.size, args.decompress, args.journal_limit) {
+            let action = if args.decompress { "decompressing" } else { "compressing" };
+            eprintln!("Error {} data: {}", action, e);
+            return 1;
+        }
+    }
+    // 21. Inversion d'endianness par mots (--swap-endian), journalisée pour --undo
+    else if let Some(width) = args.swap_endian {
+        let file_len = fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+        let len = args.size.unwrap_or(file_len.saturating_sub(offset));
+        let result = with_journal(file, offset, len, args.journal_limit, || {
+            do_swap_endian(file, offset, args.size, width)
+        });
+        if let Err(e) = result {
+            eprintln!("Error swapping endianness: {}", e);
+            return 1;
+        }
+    }
+    // 22. Surveillance du fichier (--watch), jusqu'à Ctrl+C
+    else if args.watch {
+        let size = args.size.unwrap_or(256);
+        if let Err(e) = run_watch(file, offset, size, args.display, Duration::from_millis(args.poll_interval)) {
+            eprintln!("Error watching file: {}", e);
+            return 1;
+        }
+    }
+    // 23. Carte des octets en image (--map)
+    else if let Some(output) = &args.map {
+        let Some(data) = read_range_or_report(file, offset, args.size) else {
+            return 1;
+        };
+        if let Err(e) = do_map(&data, output, args.map_width) {
+            eprintln!("Error rendering byte map: {}", e);
+            return 1;
+        }
+    }
+    // 24. Inversion de la zone (--reverse), journalisée pour --undo
+    else if args.reverse {
+        let file_len = fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+        let len = args.size.unwrap_or(file_len.saturating_sub(offset));
+        let result = with_journal(file, offset, len, args.journal_limit, || {
+            Ok(do_reverse(file, offset, args.size)?)
+        });
+        if let Err(e) = result {
+            eprintln!("Error reversing bytes: {}", e);
+            return 1;
+        }
+    }
+    // 25. Combinaison de deux fichiers (--combine)
+    else if let Some(other) = &args.combine {
+        let output = args.output_file.as_deref().unwrap_or_default();
+        let result = fs::read(file)
+            .and_then(|a| Ok((a, fs::read(other)?)))
+            .and_then(|(a, b)| {
+                let combined = combine_files(&a, &b, args.op, args.pad);
+                fs::write(output, &combined)?;
+                Ok(combined)
+            });
+        match result {
+            Ok(combined) => {
+                println!("✓ {} bytes written to {}", combined.len(), output);
+                let algo = ChecksumAlgo::Sha256;
+                println!("{} ({}): {}", algo.digester().name(), output, compute_checksum(&combined, algo));
+            }
+            Err(e) => {
+                eprintln!("Error combining files: {}", e);
+                return 1;
+            }
+        }
+    }
+    // 26. Mode Lecture (--read ou défaut si rien spécifié mais logique clap group)
     else if args.read {
         // Par défaut on lit 256 octets si --size n'est pas précisé, ou tout le fichier ?
         // L'image d'exemple montre --size 32 ou 16. Mettons une valeur par défaut raisonnable.
         let size = args.size.unwrap_or(256);
-        if let Err(e) = do_read(&args.file, offset, size) {
+        if let Err(e) = do_read(file, offset, size, args.display, logger) {
+            if let Some(l) = &logger {
+                l.log_error(&e);
+            }
             eprintln!("Error reading file: {}", e);
-            process::exit(1);
+            return 1;
         }
     } else {
         // Si aucune action n'est fournie (bien que clap gère les groupes, c'est une sécurité)
         println!("Please specify --read or --write. Use --help for more info.");
     }
+
+    0
 }
 
 /// Parse un offset sous forme "100" (dec) ou "0x10" (hex)
-fn parse_offset(input: &str) -> Result<u64, String> {
+fn parse_offset(input: &str) -> Result<u64, HexEditorError> {
     let input = input.trim();
-    if input.starts_with("0x") {
-        u64::from_str_radix(&input[2..], 16)
-            .map_err(|_| format!("Invalid hex offset: {}", input))
+    if let Some(hex) = input.strip_prefix("0x") {
+        u64::from_str_radix(hex, 16)
+            .map_err(|_| HexEditorError::InvalidOffset(input.to_string()))
     } else {
         input.parse::<u64>()
-            .map_err(|_| format!("Invalid decimal offset: {}", input))
+            .map_err(|_| HexEditorError::InvalidOffset(input.to_string()))
     }
 }
 
 /// Convertit une chaine hex "48656c" en Vec<u8>
-fn hex_string_to_bytes(hex: &str) -> Result<Vec<u8>, String> {
-    if hex.len() % 2 != 0 {
-        return Err("Hex string length must be even".to_string());
+fn hex_string_to_bytes(hex: &str) -> Result<Vec<u8>, HexEditorError> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(HexEditorError::ParseError("Hex string length must be even".to_string()));
     }
 
     (0..hex.len())
         .step_by(2)
         .map(|i| {
-            u8::from_str_radix(&hex[i..i + 2], 16)
-                .map_err(|_| format!("Invalid hex character at index {}", i))
+            // get() : None si un caractère multi-octets chevauche la paire
+            hex.get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| HexEditorError::ParseError(format!("Invalid hex character at index {}", i)))
         })
         .collect()
 }
 
-/// Logique de lecture (Hex dump)
-fn do_read(path: &str, offset: u64, size: u64) -> io::Result<()> {
+/// Formate un octet selon le mode d'affichage ("7f", "177" ou "01111111")
+fn format_byte(byte: u8, mode: DisplayMode) -> String {
+    match mode {
+        DisplayMode::Hex => format!("{:02x}", byte),
+        DisplayMode::Octal => format!("{:03o}", byte),
+        DisplayMode::Binary => format!("{:08b}", byte),
+    }
+}
+
+/// Lit `size` octets à partir de `offset` (jusqu'à la fin du fichier si None)
+fn read_range(path: &str, offset: u64, size: Option<u64>) -> io::Result<Vec<u8>> {
     let mut file = OpenOptions::new().read(true).open(path)?;
-    
-    // Seek vers l'offset
     file.seek(SeekFrom::Start(offset))?;
 
-    // Lecture du buffer
-    let mut handle = file.take(size);
     let mut buffer = Vec::new();
-    handle.read_to_end(&mut buffer)?;
-
-    // Affichage formaté (16 octets par ligne)
-    for (i, chunk) in buffer.chunks(16).enumerate() {
-        let current_offset = offset + (i as u64 * 16);
-        
-        // 1. Affichage de l'offset
-        print!("{:08x}: ", current_offset);
+    match size {
+        Some(size) => file.take(size).read_to_end(&mut buffer)?,
+        None => file.read_to_end(&mut buffer)?,
+    };
+    Ok(buffer)
+}
 
-        // 2. Affichage des octets en hex
-        for byte in chunk {
-            print!("{:02x} ", byte);
+/// read_range avec message d'erreur pour les modes en lecture seule (None => exit 1)
+fn read_range_or_report(path: &str, offset: u64, size: Option<u64>) -> Option<Vec<u8>> {
+    match read_range(path, offset, size) {
+        Ok(data) => Some(data),
+        Err(e) => {
+            eprintln!("Error reading file: {}", e);
+            None
         }
+    }
+}
 
-        // Padding si la ligne est incomplète (pour aligner l'ASCII)
-        for _ in 0..(16 - chunk.len()) {
-            print!("   ");
-        }
+/// Calcule l'empreinte des données et la retourne en hexadécimal minuscule
+fn compute_checksum(data: &[u8], algo: ChecksumAlgo) -> String {
+    algo.digester()
+        .digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
 
-        print!("|");
+/// Applique `op` octet par octet ; longueur du plus court fichier,
+/// ou du plus long avec `pad` (le plus court est complété par des zéros)
+fn combine_files(a: &[u8], b: &[u8], op: CombineOp, pad: bool) -> Vec<u8> {
+    let len = if pad { a.len().max(b.len()) } else { a.len().min(b.len()) };
+    (0..len)
+        .map(|i| op.apply(a.get(i).copied().unwrap_or(0), b.get(i).copied().unwrap_or(0)))
+        .collect()
+}
 
-        // 3. Affichage ASCII (printable ou '.')
-        for byte in chunk {
-            if *byte >= 0x20 && *byte <= 0x7E {
-                print!("{}", *byte as char);
-            } else {
-                print!(".");
+/// Extrait les suites d'au moins `min_len` caractères ASCII imprimables (comme strings(1))
+/// Retourne les positions relatives au début de `data`
+fn extract_strings(data: &[u8], min_len: usize) -> Vec<(u64, String)> {
+    let mut found = Vec::new();
+    let mut start = 0;
+    let mut current = String::new();
+
+    // Octet sentinelle non imprimable pour clore la dernière suite
+    for (i, &b) in data.iter().chain(std::iter::once(&0u8)).enumerate() {
+        if (0x20..=0x7E).contains(&b) {
+            if current.is_empty() {
+                start = i;
+            }
+            current.push(b as char);
+        } else {
+            if current.len() >= min_len.max(1) {
+                found.push((start as u64, current.clone()));
             }
+            current.clear();
         }
-        println!("|");
     }
 
-    Ok(())
+    found
 }
 
-/// Logique d'écriture
-fn do_write(path: &str, offset: u64, hex_str: &str) -> Result<(), String> {
-    let bytes = hex_string_to_bytes(hex_str)?;
-    
-    // Ouverture en mode write (et read pour ne pas tronquer si besoin, 
-    // mais OpenOptions::write(true) sans truncate préserve le contenu existant)
-    let mut file = OpenOptions::new()
-        .write(true)
-        .create(true) // Créer si n'existe pas
-        .open(path)
-        .map_err(|e| e.to_string())?;
+/// Même principe en UTF-16LE : caractère imprimable suivi d'un octet nul
+fn extract_wide_strings(data: &[u8], min_len: usize) -> Vec<(u64, String)> {
+    let mut found = Vec::new();
 
-    // Seek
-    file.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+    // On teste les deux alignements possibles (offset pair et impair)
+    for align in 0..2 {
+        let mut start = 0;
+        let mut current = String::new();
+        let pairs = data.get(align..).unwrap_or(&[]).chunks_exact(2);
 
-    // Écriture
-    file.write_all(&bytes).map_err(|e| e.to_string())?;
+        for (i, pair) in pairs.chain(std::iter::once(&[0u8, 1u8][..])).enumerate() {
+            if (0x20..=0x7E).contains(&pair[0]) && pair[1] == 0 {
+                if current.is_empty() {
+                    start = align + i * 2;
+                }
+                current.push(pair[0] as char);
+            } else {
+                if current.len() >= min_len.max(1) {
+                    found.push((start as u64, current.clone()));
+                }
+                current.clear();
+            }
+        }
+    }
 
-    // Feedback utilisateur comme demandé dans l'image exemple
-    println!("writing {} bytes at offset {:#010x}", bytes.len(), offset);
-    print!("Hex: ");
-    for b in &bytes { print!("{:02x} ", b); }
-    println!();
-    print!("ASCII: ");
-    for b in &bytes {
-        let c = if *b >= 0x20 && *b <= 0x7E { *b as char } else { '.' };
-        print!("{}", c);
+    found.sort_by_key(|(pos, _)| *pos);
+    found
+}
+
+/// Entropie de Shannon d'un bloc, en bits par octet (0.0 à 8.0)
+fn shannon_entropy(block: &[u8]) -> f64 {
+    if block.is_empty() {
+        return 0.0;
     }
-    println!("\n✓ successfully written");
 
-    Ok(())
-}
\ No newline at end of file
+    let mut counts = [0usize; 256];
+    for &b in block {
+        counts[b as usize] += 1;
+    }
+
+    let len = block.len() as f64;
+    counts
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / len;
+            p * (1.0 / p).log2()
+        })
+        .sum()
+}
+
+/// Affiche l'entropie par bloc de 256 octets et un sparkline du profil complet
+fn do_entropy(data: &[u8], offset: u64, threshold: Option<f64>) {
+    const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let entropies: Vec<f64> = data.chunks(256).map(shannon_entropy).collect();
+
+    for (i, e) in entropies.iter().enumerate() {
+        // > 7.5 : probablement compressé/chiffré, < 1.0 : zone creuse ou padding
+        let hint = if *e > 7.5 {
+            "  (compressed/encrypted?)"
+        } else if *e < 1.0 {
+            "  (sparse/padding)"
+        } else {
+            ""
+        };
+        println!("{:#010x}: {:.3}{}", offset + (i * 256) as u64, e, hint);
+    }
+
+    let sparkline: String = entropies
+        .iter()
+        .map(|e| SPARKS[(*e as usize).min(7)])
+        .collect();
+    println!("\nEntropy profile: {}", sparkline);
+
+    if let Some(limit) = threshold {
+        for (i, e) in entropies.iter().enumerate() {
+            if *e > limit {
+                println!(
+                    "[ALERT] block at {:#010x} has entropy {:.3} > {:.3}",
+                    offset + (i * 256) as u64,
+                    e,
+                    limit
+                );
+            }
+        }
+    }
+}
+
+/// Nombre d'occurrences de chaque valeur d'octet
+fn byte_frequency(data: &[u8]) -> [u32; 256] {
+    let mut freq = [0u32; 256];
+    for &b in data {
+        freq[b as usize] += 1;
+    }
+    freq
+}
+
+/// Affiche l'histogramme des octets présents (barre la plus longue : 40 caractères)
+fn do_byte_freq(data: &[u8], sort: ByteFreqSort, xor_hint: bool) {
+    let freq = byte_frequency(data);
+
+    let mut rows: Vec<(u8, u32)> = (0..=255u8)
+        .map(|b| (b, freq[b as usize]))
+        .filter(|&(_, count)| count > 0)
+        .collect();
+    if sort == ByteFreqSort::Count {
+        rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    }
+
+    let max = rows.iter().map(|&(_, count)| count).max().unwrap_or(0);
+    let count_width = max.to_string().len();
+    for &(byte, count) in &rows {
+        let bar = "█".repeat(((count as u64 * 40).div_ceil(max as u64)) as usize);
+        let percent = count as f64 * 100.0 / data.len() as f64;
+        println!(
+            "0x{:02X} ({:>3}): {:<40} {:>count_width$}  ({:.1}%)",
+            byte, byte, bar, count, percent,
+        );
+    }
+    println!("{} bytes, {} distinct values", data.len(), rows.len());
+
+    // Pour un XOR à clé d'un octet sur du texte, l'octet le plus fréquent est
+    // souvent l'espace (0x20) chiffré : clé probable = octet ^ 0x20
+    if xor_hint {
+        let mut by_count: Vec<(u8, u32)> = (0..=255u8).map(|b| (b, freq[b as usize])).collect();
+        by_count.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        println!("\nLikely XOR keys (most frequent byte ^ 0x20):");
+        for &(byte, count) in by_count.iter().take(3).filter(|&&(_, count)| count > 0) {
+            println!("  key 0x{:02X} (from 0x{:02X}, {} occurrences)", byte ^ 0x20, byte, count);
+        }
+    }
+}
+
+/// Logique de lecture (Hex dump)
+fn do_read(path: &str, offset: u64, size: u64, mode: DisplayMode, logger: Option<VerboseLogger>) -> Result<(), HexEditorError> {
+    let mut file = OpenOptions::new().read(true).open(path)?;
+
+    // La colonne d'offset s'adapte à la taille du fichier (8 chiffres minimum)
+    let file_len = file.metadata()?.len();
+    let offset_width = (format!("{:x}", file_len).len()).max(8);
+    if let Some(l) = &logger {
+        l.log_file_size("before", file_len);
+    }
+    
+    // Seek vers l'offset
+    file.seek(SeekFrom::Start(offset))?;
+    if let Some(l) = &logger {
+        l.log_seek(offset);
+    }
+
+    // Lecture du buffer
+    let mut handle = file.take(size);
+    let mut buffer = Vec::new();
+    handle.read_to_end(&mut buffer)?;
+    if let Some(l) = &logger {
+        l.log_read(size, buffer.len());
+        l.log_file_size("after", file_len);
+    }
+
+    // Affichage formaté (16 octets par ligne en hex, 8 en octal, 4 en binaire)
+    let per_line = mode.bytes_per_line();
+    for (i, chunk) in buffer.chunks(per_line).enumerate() {
+        let current_offset = offset + (i * per_line) as u64;
+        
+        // 1. Affichage de l'offset
+        print!("{:0width$x}: ", current_offset, width = offset_width);
+
+        // 2. Affichage des octets dans le format choisi
+        for byte in chunk {
+            print!("{} ", format_byte(*byte, mode));
+        }
+
+        // Padding si la ligne est incomplète (pour aligner l'ASCII)
+        let cell = format_byte(0, mode).len() + 1;
+        for _ in 0..(per_line - chunk.len()) {
+            print!("{:cell$}", "", cell = cell);
+        }
+
+        print!("|");
+
+        // 3. Affichage ASCII (printable ou '.')
+        for byte in chunk {
+            if *byte >= 0x20 && *byte <= 0x7E {
+                print!("{}", *byte as char);
+            } else {
+                print!(".");
+            }
+        }
+        println!("|");
+    }
+
+    Ok(())
+}
+
+/// Préfixe des lignes de confirmation en --dry-run
+fn dry_run_prefix(dry_run: bool) -> &'static str {
+    if dry_run { "[DRY-RUN] " } else { "" }
+}
+
+/// Logique d'écriture (avec `dry_run`, le fichier n'est même pas ouvert)
+fn do_write(
+    path: &str,
+    offset: u64,
+    hex_str: &str,
+    mode: DisplayMode,
+    dry_run: bool,
+    logger: Option<VerboseLogger>,
+) -> Result<(), HexEditorError> {
+    let bytes = hex_string_to_bytes(hex_str)?;
+    let prefix = dry_run_prefix(dry_run);
+
+    if !dry_run {
+        // Ouverture en mode write (et read pour ne pas tronquer si besoin, 
+        // mais OpenOptions::write(true) sans truncate préserve le contenu existant)
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true) // Créer si n'existe pas
+            .truncate(false) // Ne jamais tronquer : on écrit par-dessus l'existant
+            .open(path)?;
+        if let Some(l) = &logger {
+            l.log_file_size("before", file.metadata()?.len());
+        }
+
+        // Seek
+        file.seek(SeekFrom::Start(offset))?;
+        if let Some(l) = &logger {
+            l.log_seek(offset);
+        }
+
+        // Écriture
+        file.write_all(&bytes)?;
+        if let Some(l) = &logger {
+            l.log_write(bytes.len());
+            l.log_file_size("after", file.metadata()?.len());
+        }
+    }
+
+    // Feedback utilisateur comme demandé dans l'image exemple
+    println!("{}writing {} bytes at offset {:#010x}", prefix, bytes.len(), offset);
+    print!("{}{}: ", prefix, mode.label());
+    for b in &bytes { print!("{} ", format_byte(*b, mode)); }
+    println!();
+    print!("{}ASCII: ", prefix);
+    for b in &bytes {
+        let c = if *b >= 0x20 && *b <= 0x7E { *b as char } else { '.' };
+        print!("{}", c);
+    }
+    println!();
+    if dry_run {
+        println!("{}✓ nothing written", prefix);
+    } else {
+        println!("✓ successfully written");
+    }
+
+    Ok(())
+}
+
+/// Logique de remplissage : `size` octets à partir de `offset` avec le motif répété
+fn do_fill(
+    path: &str,
+    offset: u64,
+    size: u64,
+    pattern: &[u8],
+    extend: bool,
+    mode: DisplayMode,
+    dry_run: bool,
+) -> Result<(), HexEditorError> {
+    if pattern.is_empty() {
+        return Err(HexEditorError::InvalidArgument("Fill pattern must not be empty".to_string()));
+    }
+    let prefix = dry_run_prefix(dry_run);
+
+    // On refuse de dépasser la fin du fichier, sauf avec --extend
+    let file_len = fs::metadata(path)?.len();
+    let end = offset
+        .checked_add(size)
+        .ok_or_else(|| HexEditorError::InvalidArgument("offset + size overflows".to_string()))?;
+    if end > file_len && !extend {
+        return Err(HexEditorError::OutOfBounds { offset, end, file_len });
+    }
+
+    // Motif répété cycliquement sur exactement `size` octets
+    let bytes: Vec<u8> = pattern.iter().cycle().take(size as usize).copied().collect();
+
+    if !dry_run {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(&bytes)?;
+    }
+
+    // Feedback utilisateur dans le même style que do_write (aperçu limité à 32 octets)
+    println!(
+        "{}filling {} bytes at offset {:#010x} ({} x {}-byte pattern)",
+        prefix,
+        bytes.len(),
+        offset,
+        size as f64 / pattern.len() as f64,
+        pattern.len()
+    );
+    let preview = &bytes[..bytes.len().min(32)];
+    let more = if bytes.len() > preview.len() { "..." } else { "" };
+    print!("{}{}: ", prefix, mode.label());
+    for b in preview { print!("{} ", format_byte(*b, mode)); }
+    println!("{}", more);
+    print!("{}ASCII: ", prefix);
+    for b in preview {
+        let c = if *b >= 0x20 && *b <= 0x7E { *b as char } else { '.' };
+        print!("{}", c);
+    }
+    println!("{}", more);
+    if dry_run {
+        println!("{}✓ nothing written", prefix);
+    } else {
+        println!("✓ successfully written");
+    }
+
+    Ok(())
+}
+
+/// Logique d'import base64 : même écriture que --write, avec contrôle de taille
+fn do_import_b64(
+    path: &str,
+    offset: u64,
+    bytes: &[u8],
+    extend: bool,
+    mode: DisplayMode,
+) -> Result<(), HexEditorError> {
+    // Les données décodées doivent tenir dans le fichier, sauf avec --extend
+    let file_len = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let end = offset + bytes.len() as u64;
+    if end > file_len && !extend {
+        return Err(HexEditorError::OutOfBounds { offset, end, file_len });
+    }
+
+    do_write(path, offset, &bytes_to_hex(bytes), mode, false, None)?;
+    println!("Base64: {}", BASE64.encode(bytes));
+    Ok(())
+}
+
+/// Logique XOR : applique la clé (répétée) sur la zone et réécrit en place
+fn do_xor(path: &str, offset: u64, size: Option<u64>, key: &[u8]) -> Result<(), HexEditorError> {
+    if key.is_empty() {
+        return Err(HexEditorError::InvalidArgument("XOR key must not be empty".to_string()));
+    }
+
+    let original = read_range(path, offset, size)?;
+    let xored: Vec<u8> = original
+        .iter()
+        .zip(key.iter().cycle())
+        .map(|(b, k)| b ^ k)
+        .collect();
+
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    file.write_all(&xored)?;
+
+    println!(
+        "XOR {} bytes at offset {:#010x} with {}-byte key",
+        xored.len(),
+        offset,
+        key.len()
+    );
+    println!("Before:");
+    print_dump(&original[..original.len().min(32)], offset);
+    println!("After:");
+    print_dump(&xored[..xored.len().min(32)], offset);
+    println!("✓ successfully written");
+
+    Ok(())
+}
+
+/// Compresse `data` en un flux gzip complet (niveau par défaut)
+fn gzip_compress(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+/// Décompresse le premier membre gzip de `data` (les octets qui le suivent sont ignorés)
+fn gzip_decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut decoded)?;
+    Ok(decoded)
+}
+
+/// Remplace la zone par sa version (dé)compressée, écrite à partir de `offset`.
+/// Un résultat plus long écrase les octets suivants, un résultat plus court laisse
+/// la fin de la zone intacte : dans les deux cas on le signale.
+fn do_gzip(path: &str, offset: u64, size: Option<u64>, decompress: bool, limit: usize) -> Result<(), HexEditorError> {
+    let original = read_range(path, offset, size)?;
+    let result = if decompress {
+        gzip_decompress(&original).map_err(|e| HexEditorError::ParseError(format!("not a gzip stream: {}", e)))?
+    } else {
+        gzip_compress(&original)?
+    };
+
+    let (compressed, uncompressed) = if decompress { (&original, &result) } else { (&result, &original) };
+    let ratio = if uncompressed.is_empty() { 0.0 } else { compressed.len() as f64 / uncompressed.len() as f64 * 100.0 };
+
+    if result.len() > original.len() {
+        eprintln!(
+            "Warning: result ({} bytes) is larger than the source range ({} bytes), {} following bytes overwritten",
+            result.len(),
+            original.len(),
+            result.len() - original.len()
+        );
+    } else if result.len() < original.len() {
+        println!(
+            "Note: {} bytes of the source range left unchanged after the result",
+            original.len() - result.len()
+        );
+    }
+
+    with_journal(path, offset, result.len() as u64, limit, || {
+        let mut file = OpenOptions::new().write(true).open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(&result)?;
+        Ok(())
+    })?;
+
+    let verb = if decompress { "Decompressed" } else { "Compressed" };
+    println!(
+        "{} {} -> {} bytes at offset {:#010x} (ratio {:.1}%)",
+        verb,
+        original.len(),
+        result.len(),
+        offset,
+        ratio
+    );
+    print_dump(&result[..result.len().min(32)], offset);
+    println!("✓ successfully written");
+    Ok(())
+}
+
+/// Inverse l'ordre des octets de chaque mot de `word_size` octets (0x01020304 -> 0x04030201)
+fn swap_endian(data: &mut [u8], word_size: usize) -> Result<(), String> {
+    if word_size == 0 || !data.len().is_multiple_of(word_size) {
+        return Err(format!("size {} is not a multiple of the word width {}", data.len(), word_size));
+    }
+    for word in data.chunks_exact_mut(word_size) {
+        word.reverse();
+    }
+    Ok(())
+}
+
+/// Logique --swap-endian : relit la zone, inverse chaque mot et réécrit en place
+fn do_swap_endian(path: &str, offset: u64, size: Option<u64>, word_size: usize) -> Result<(), HexEditorError> {
+    let original = read_range(path, offset, size)?;
+    let mut swapped = original.clone();
+    swap_endian(&mut swapped, word_size).map_err(HexEditorError::InvalidArgument)?;
+
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    file.write_all(&swapped)?;
+
+    println!(
+        "Swapped {} {}-byte words at offset {:#010x}",
+        swapped.len() / word_size,
+        word_size,
+        offset
+    );
+    println!("Before:");
+    print_dump(&original[..original.len().min(32)], offset);
+    println!("After:");
+    print_dump(&swapped[..swapped.len().min(32)], offset);
+    println!("✓ successfully written");
+
+    Ok(())
+}
+
+/// Logique --reverse : toute la zone à l'envers (pas mot par mot comme --swap-endian)
+fn do_reverse(path: &str, offset: u64, size: Option<u64>) -> io::Result<()> {
+    let mut data = read_range(path, offset, size)?;
+    data.reverse();
+
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    file.write_all(&data)?;
+
+    println!("Reversed {} bytes at offset {:#010x}", data.len(), offset);
+    print_dump(&data[..data.len().min(32)], offset);
+    println!("✓ successfully written");
+    Ok(())
+}
+
+/// Logique d'insertion : le fichier est relu en entier, coupé à `offset`,
+/// puis réécrit avec les nouveaux octets au milieu (journalisé pour --undo)
+fn do_insert(path: &str, offset: u64, bytes: &[u8], limit: usize) -> Result<(), HexEditorError> {
+    let mut data = fs::read(path)?;
+    let file_len = data.len() as u64;
+    if offset > file_len {
+        return Err(HexEditorError::InvalidArgument(format!(
+            "offset {:#010x} is past the end of the file ({} bytes)",
+            offset, file_len
+        )));
+    }
+
+    data.splice(offset as usize..offset as usize, bytes.iter().copied());
+    replace_file(path, &data)?;
+    push_journal_entry(path, JournalOp::Insert, offset, &[], bytes, file_len, limit)?;
+
+    println!("inserting {} bytes at offset {:#010x}", bytes.len(), offset);
+    print_dump(&bytes[..bytes.len().min(32)], offset);
+    println!("✓ successfully inserted, new file size: {} bytes", data.len());
+
+    Ok(())
+}
+
+/// Logique de suppression : même stratégie que do_insert, la plage est bornée
+/// à la fin du fichier (journalisé pour --undo)
+fn do_delete(path: &str, offset: u64, size: u64, limit: usize) -> Result<(), HexEditorError> {
+    let mut data = fs::read(path)?;
+    let file_len = data.len() as u64;
+    if offset > file_len {
+        return Err(HexEditorError::InvalidArgument(format!(
+            "offset {:#010x} is past the end of the file ({} bytes)",
+            offset, file_len
+        )));
+    }
+
+    let end = offset.saturating_add(size).min(file_len);
+    let removed: Vec<u8> = data.drain(offset as usize..end as usize).collect();
+    replace_file(path, &data)?;
+    push_journal_entry(path, JournalOp::Delete, offset, &removed, &[], file_len, limit)?;
+
+    if end - offset < size {
+        println!("requested {} bytes, clamped to end of file", size);
+    }
+    println!("deleting {} bytes at offset {:#010x}", removed.len(), offset);
+    print_dump(&removed[..removed.len().min(32)], offset);
+    println!("✓ {} bytes deleted, new file size: {} bytes", removed.len(), data.len());
+
+    Ok(())
+}
+
+/// Logique de redimensionnement : `allow_shrink` pour --truncate, sinon (--resize)
+/// le fichier n'est jamais raccourci
+fn do_resize(path: &str, new_len: u64, allow_shrink: bool) -> io::Result<()> {
+    let file = OpenOptions::new().write(true).open(path)?;
+    let old_len = file.metadata()?.len();
+
+    if !allow_shrink && new_len <= old_len {
+        println!("file is already {} bytes (>= {}), nothing to do", old_len, new_len);
+        return Ok(());
+    }
+    if allow_shrink && new_len > old_len {
+        eprintln!(
+            "Warning: --truncate {} is larger than the file ({} bytes), padding with zeros",
+            new_len, old_len
+        );
+    }
+
+    file.set_len(new_len)?;
+    println!("resizing {}: {} -> {} bytes", path, old_len, new_len);
+    println!("✓ successfully resized");
+    Ok(())
+}
+
+/// Remplace tout le contenu du fichier : écriture dans un fichier temporaire voisin
+/// puis fs::rename, pour ne jamais laisser un fichier à moitié réécrit
+fn replace_file(path: &str, data: &[u8]) -> io::Result<()> {
+    let tmp = format!("{}.hextool.tmp", path);
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+/// Petit dump hex+ASCII (même format que do_read) pour les confirmations
+fn print_dump(bytes: &[u8], offset: u64) {
+    for (i, chunk) in bytes.chunks(16).enumerate() {
+        print!("{:08x}: ", offset + (i as u64 * 16));
+        for byte in chunk {
+            print!("{:02x} ", byte);
+        }
+        print!("{}|", "   ".repeat(16 - chunk.len()));
+        for byte in chunk {
+            let c = if *byte >= 0x20 && *byte <= 0x7E { *byte as char } else { '.' };
+            print!("{}", c);
+        }
+        println!("|");
+    }
+}
+
+/// Logique de recherche d'un motif hexadécimal
+fn do_find(
+    path: &str,
+    offset: u64,
+    size: Option<u64>,
+    hex_str: &str,
+    find_all: bool,
+) -> Result<bool, HexEditorError> {
+    let needle = hex_string_to_bytes(hex_str)?;
+    if needle.is_empty() || needle.len() > 256 {
+        return Err(HexEditorError::InvalidArgument("Search pattern must be between 1 and 256 bytes".to_string()));
+    }
+
+    // Si --size est donné, on limite la zone de recherche
+    let haystack = read_range(path, offset, size)?;
+
+    let matches = find_pattern(&haystack, &needle);
+    let shown = if find_all { matches.len() } else { matches.len().min(1) };
+
+    for m in &matches[..shown] {
+        println!("{:#010x}", offset + m);
+    }
+    if matches.is_empty() {
+        println!("Pattern not found");
+    } else if find_all {
+        println!("{} match(es) found", matches.len());
+    }
+
+    Ok(!matches.is_empty())
+}
+
+/// Recherche toutes les occurrences de needle dans haystack (Boyer-Moore-Horspool)
+/// Retourne les positions relatives au début de haystack
+fn find_pattern(haystack: &[u8], needle: &[u8]) -> Vec<u64> {
+    let n = needle.len();
+    let mut matches = Vec::new();
+    if n == 0 || n > haystack.len() {
+        return matches;
+    }
+
+    // Table de décalage : distance entre la dernière occurrence d'un octet et la fin du motif
+    let mut shift = [n; 256];
+    for (i, &b) in needle[..n - 1].iter().enumerate() {
+        shift[b as usize] = n - 1 - i;
+    }
+
+    let mut pos = 0;
+    while pos + n <= haystack.len() {
+        if &haystack[pos..pos + n] == needle {
+            matches.push(pos as u64);
+        }
+        pos += shift[haystack[pos + n - 1] as usize];
+    }
+
+    matches
+}
+
+/// Logique de comparaison de deux fichiers (diff hexadécimal côte à côte)
+fn do_diff(path_a: &str, path_b: &str, context: usize) -> Result<bool, HexEditorError> {
+    // Le nom du fichier fautif est ajouté au message, le type d'erreur est conservé
+    let read = |path: &str| fs::read(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path, e)));
+    let a = read(path_a)?;
+    let b = read(path_b)?;
+
+    let lines = diff_files(&a, &b, context);
+    let identical = lines.iter().all(|l| matches!(l, DiffLine::Same(..)));
+
+    println!("--- {} ({} bytes)", path_a, a.len());
+    println!("+++ {} ({} bytes)", path_b, b.len());
+
+    // Pas de codes ANSI quand la sortie est redirigée vers un fichier ou un pipe
+    let color = io::stdout().is_terminal();
+    let cells = |bytes: &[u8], other: &[u8]| format_hex_cells(bytes, other, color);
+
+    let mut prev_offset: Option<u64> = None;
+    for line in &lines {
+        // Séparateur quand des lignes identiques ont été masquées
+        if let Some(prev) = prev_offset
+            && line.offset() != prev + 16
+        {
+            println!("...");
+        }
+        prev_offset = Some(line.offset());
+
+        match line {
+            DiffLine::Same(offset, bytes) => {
+                let hex = cells(bytes, bytes);
+                println!("  {:#010x}: {} | {}", offset, hex, hex);
+            }
+            DiffLine::Different(offset, left, right) => {
+                println!(
+                    "! {:#010x}: {} | {}",
+                    offset,
+                    cells(left, right),
+                    cells(right, left)
+                );
+            }
+            DiffLine::OnlyIn(Side::Left, offset, bytes) => {
+                let empty = cells(&[], &[]);
+                println!("- {:#010x}: {} | {}", offset, cells(bytes, &[]), empty);
+            }
+            DiffLine::OnlyIn(Side::Right, offset, bytes) => {
+                let empty = cells(&[], &[]);
+                println!("+ {:#010x}: {} | {}", offset, empty, cells(bytes, &[]));
+            }
+        }
+    }
+
+    if identical {
+        println!("Files are identical");
+    }
+
+    Ok(identical)
+}
+
+/// Formate 16 cellules hex, en rouge (avec `color`) les octets qui diffèrent de l'autre côté
+fn format_hex_cells(bytes: &[u8], other: &[u8], color: bool) -> String {
+    let mut out = String::new();
+    for i in 0..16 {
+        if i > 0 {
+            out.push(' ');
+        }
+        match bytes.get(i) {
+            Some(b) if other.get(i) == Some(b) => out.push_str(&format!("{:02x}", b)),
+            Some(b) if color => out.push_str(&format!("\x1b[31m{:02x}\x1b[0m", b)),
+            Some(b) => out.push_str(&format!("{:02x}", b)),
+            None => out.push_str("  "),
+        }
+    }
+    out
+}
+
+/// Compare deux buffers ligne par ligne (16 octets) et ne garde que les lignes
+/// différentes plus `context` lignes identiques autour de chacune
+fn diff_files(a: &[u8], b: &[u8], context: usize) -> Vec<DiffLine> {
+    let line_count = a.len().max(b.len()).div_ceil(16);
+
+    let all: Vec<DiffLine> = (0..line_count)
+        .map(|i| {
+            let offset = (i * 16) as u64;
+            let left = a.get(i * 16..((i + 1) * 16).min(a.len())).unwrap_or(&[]);
+            let right = b.get(i * 16..((i + 1) * 16).min(b.len())).unwrap_or(&[]);
+            if left.is_empty() {
+                DiffLine::OnlyIn(Side::Right, offset, right.to_vec())
+            } else if right.is_empty() {
+                DiffLine::OnlyIn(Side::Left, offset, left.to_vec())
+            } else if left == right {
+                DiffLine::Same(offset, left.to_vec())
+            } else {
+                DiffLine::Different(offset, left.to_vec(), right.to_vec())
+            }
+        })
+        .collect();
+
+    // Indices des lignes à conserver : chaque différence et son contexte
+    let mut keep = vec![false; all.len()];
+    for (i, line) in all.iter().enumerate() {
+        if !matches!(line, DiffLine::Same(..)) {
+            let start = i.saturating_sub(context);
+            let end = (i + context).min(all.len() - 1);
+            keep[start..=end].iter_mut().for_each(|k| *k = true);
+        }
+    }
+
+    all.into_iter()
+        .zip(keep)
+        .filter_map(|(line, k)| if k { Some(line) } else { None })
+        .collect()
+}
+
+// ==========================================
+// PATCH BINAIRE (--patch)
+// ==========================================
+
+/// Une écriture du fichier de patch : offset et octets
+type Patch = (u64, Vec<u8>);
+
+/// Une ligne "<offset_hex> <hex_bytes>" par écriture (offset avec ou sans 0x, blancs
+/// ignorés dans les octets), commentaires en #. Une ligne invalide est signalée et
+/// ignorée, ou fait échouer tout le fichier avec `strict`.
+/// Renvoie les patchs valides et le nombre de lignes ignorées.
+fn parse_patch_file(content: &str, strict: bool) -> Result<(Vec<Patch>, usize), String> {
+    let mut patches = Vec::new();
+    let mut skipped = 0;
+
+    for (n, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let parsed = line
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| "expected \"<offset_hex> <hex_bytes>\"".to_string())
+            .and_then(|(offset, hex)| {
+                let digits = offset.strip_prefix("0x").unwrap_or(offset);
+                let offset = u64::from_str_radix(digits, 16).map_err(|_| format!("invalid hex offset: {}", offset))?;
+                let hex: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
+                let bytes = hex_string_to_bytes(&hex).map_err(|e| e.to_string())?;
+                Ok((offset, bytes))
+            });
+
+        match parsed {
+            Ok(patch) => patches.push(patch),
+            Err(e) if strict => return Err(format!("line {}: {}", n + 1, e)),
+            Err(e) => {
+                eprintln!("Warning: line {}: {}, skipped", n + 1, e);
+                skipped += 1;
+            }
+        }
+    }
+    Ok((patches, skipped))
+}
+
+/// Applique les patchs dans l'ordre, renvoie le nombre de réussites.
+/// Un échec d'écriture est signalé puis ignoré, sauf avec --strict qui arrête là.
+fn do_patch(path: &str, patches: &[Patch], args: &Args, logger: Option<VerboseLogger>) -> usize {
+    let mut applied = 0;
+
+    for (offset, bytes) in patches {
+        let hex = bytes_to_hex(bytes);
+        let result = if args.dry_run {
+            do_write(path, *offset, &hex, args.display, true, logger)
+        } else {
+            with_journal(path, *offset, bytes.len() as u64, args.journal_limit, || {
+                do_write(path, *offset, &hex, args.display, false, logger)
+            })
+        };
+
+        match result {
+            Ok(()) => applied += 1,
+            Err(e) => {
+                eprintln!("Error patching {:#010x}: {}", offset, e);
+                if args.strict {
+                    break;
+                }
+            }
+        }
+    }
+    applied
+}
+
+// ==========================================
+// DÉCOUPAGE / RÉASSEMBLAGE (--split, --split-size, --join)
+// ==========================================
+
+fn part_path(path: &str, index: usize) -> String {
+    format!("{}.part{}", path, index)
+}
+
+/// `n` parties de ceil(len / n) octets : seule la dernière peut être plus petite
+fn do_split(path: &str, n: usize) -> io::Result<()> {
+    let len = fs::metadata(path)?.len();
+    let part_size = len.div_ceil(n as u64).max(1);
+    write_parts(path, part_size, n)
+}
+
+/// Parties de `part_size` octets exactement, la dernière recevant le reste
+fn split_by_size(path: &str, part_size: u64) -> io::Result<()> {
+    let len = fs::metadata(path)?.len();
+    let count = len.div_ceil(part_size).max(1) as usize;
+    write_parts(path, part_size, count)
+}
+
+fn write_parts(path: &str, part_size: u64, count: usize) -> io::Result<()> {
+    let mut reader = BufReader::new(fs::File::open(path)?);
+
+    for index in 0..count {
+        let part = part_path(path, index);
+        let mut writer = BufWriter::new(fs::File::create(&part)?);
+        let written = io::copy(&mut (&mut reader).take(part_size), &mut writer)?;
+        writer.flush()?;
+        println!("{}: {} bytes", part, written);
+    }
+
+    // Restes d'un découpage précédent plus fin : --join les prendrait pour la suite
+    let mut stale = count;
+    while fs::remove_file(part_path(path, stale)).is_ok() {
+        stale += 1;
+    }
+    println!("✓ split into {} parts", count);
+    Ok(())
+}
+
+/// Concatène <path>.part0, <path>.part1... dans l'ordre numérique (part10 après part9).
+/// Les indices doivent se suivre depuis 0 : une partie manquante est une erreur.
+fn do_join(path: &str, output: &str) -> io::Result<()> {
+    let base = Path::new(path);
+    let dir = match base.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let prefix = format!("{}.part", base.file_name().map(|n| n.to_string_lossy()).unwrap_or_default());
+
+    let mut indices: Vec<usize> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().to_str()?.strip_prefix(&prefix)?.parse().ok())
+        .collect();
+    indices.sort_unstable();
+
+    if indices.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("no {}.part* files found", path)));
+    }
+    if let Some(missing) = (0..).zip(&indices).find_map(|(expected, &index)| (expected != index).then_some(expected)) {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("missing {}", part_path(path, missing))));
+    }
+
+    let mut writer = BufWriter::new(fs::File::create(output)?);
+    let mut total = 0;
+    for &index in &indices {
+        let mut reader = BufReader::new(fs::File::open(part_path(path, index))?);
+        total += io::copy(&mut reader, &mut writer)?;
+    }
+    writer.flush()?;
+
+    println!("✓ joined {} parts into {} ({} bytes)", indices.len(), output, total);
+    Ok(())
+}
+
+// ==========================================
+// ENTRÉE HEX SUR STDIN (--hex-stdin)
+// ==========================================
+
+/// Décode `hex` (blancs ignorés) dans un fichier temporaire, supprimé quand il est droppé
+fn hex_stdin_to_tempfile(hex: &str) -> io::Result<NamedTempFile> {
+    let hex: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
+    let bytes = hex_string_to_bytes(&hex).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+    let mut temp = NamedTempFile::new()?;
+    temp.write_all(&bytes)?;
+    temp.flush()?;
+    Ok(temp)
+}
+
+/// Après une opération d'écriture, affiche le contenu final en hex (réutilisable en entrée)
+/// et supprime les journaux créés à côté du fichier temporaire
+fn finish_hex_stdin(temp: NamedTempFile, initial: &[u8]) {
+    let path = temp.path().to_string_lossy().into_owned();
+    match fs::read(&path) {
+        Ok(data) if data != initial => println!("Result: {}", bytes_to_hex(&data)),
+        Ok(_) => {}
+        Err(e) => eprintln!("Error reading temporary file: {}", e),
+    }
+    let _ = fs::remove_file(journal_path(&path));
+    let _ = fs::remove_file(redo_path(&path));
+}
+
+// ==========================================
+// CARTE DES OCTETS (--map)
+// ==========================================
+
+// Couleurs de la carte, réparties uniformément sur 00..FF
+const MAP_STOPS: [(u8, u8, u8); 3] = [(0, 0, 96), (160, 0, 160), (255, 32, 32)];
+
+/// Interpolation linéaire entre des couleurs réparties uniformément sur 00..FF
+/// (même modèle que ColorScheme::apply de rust_04)
+fn gradient(stops: &[(u8, u8, u8)], val: u8) -> (u8, u8, u8) {
+    let segments = (stops.len() - 1) as f32;
+    let pos = val as f32 / 255.0 * segments;
+    let i = (pos as usize).min(stops.len() - 2);
+    let t = pos - i as f32;
+
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    let (from, to) = (stops[i], stops[i + 1]);
+    (lerp(from.0, to.0), lerp(from.1, to.1), lerp(from.2, to.2))
+}
+
+/// Un pixel par octet, ligne par ligne ; la fin de la dernière ligne reste noire
+fn do_map(data: &[u8], output: &str, width: u32) -> Result<(), HexEditorError> {
+    if data.is_empty() {
+        return Err(HexEditorError::InvalidArgument("nothing to render: the range is empty".to_string()));
+    }
+    let height = data.len().div_ceil(width as usize) as u32;
+
+    let mut img = RgbImage::new(width, height);
+    for (i, &byte) in data.iter().enumerate() {
+        let (r, g, b) = gradient(&MAP_STOPS, byte);
+        img.put_pixel(i as u32 % width, i as u32 / width, Rgb([r, g, b]));
+    }
+    img.save(output)
+        .map_err(|e| HexEditorError::InvalidArgument(format!("{}: {}", output, e)))?;
+
+    println!("✓ {} bytes rendered to {} ({}x{})", data.len(), output, width, height);
+    Ok(())
+}
+
+// ==========================================
+// SURVEILLANCE (--watch)
+// ==========================================
+
+/// Détecte les modifications d'un fichier par stat (date de modification + taille)
+struct FileWatcher {
+    path: String,
+    /// Dernier état vu ; None si le fichier était inaccessible
+    last: Cell<Option<(SystemTime, u64)>>,
+}
+
+impl FileWatcher {
+    fn new(path: &str) -> Self {
+        let watcher = FileWatcher { path: path.to_string(), last: Cell::new(None) };
+        watcher.last.set(watcher.stamp());
+        watcher
+    }
+
+    fn stamp(&self) -> Option<(SystemTime, u64)> {
+        let metadata = fs::metadata(&self.path).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+
+    /// true si le fichier a changé (ou est apparu/disparu) depuis le dernier appel
+    fn poll(&self) -> bool {
+        let current = self.stamp();
+        let changed = current != self.last.get();
+        self.last.set(current);
+        changed
+    }
+}
+
+/// Horodatage UTC au format ISO 8601 (ex. 2024-05-01T12:34:56Z)
+fn iso8601(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+
+    // Jours depuis 1970-01-01 -> date civile (algorithme de H. Hinnant)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, rem / 3600, rem % 3600 / 60, rem % 60
+    )
+}
+
+/// Efface l'écran et réaffiche en-tête + dump à chaque modification ; Ctrl+C pour quitter
+fn run_watch(path: &str, offset: u64, size: u64, mode: DisplayMode, interval: Duration) -> io::Result<()> {
+    let watcher = FileWatcher::new(path);
+    let mut stdout = io::stdout();
+
+    loop {
+        execute!(stdout, Clear(ClearType::All), MoveTo(0, 0))?;
+        match fs::metadata(path) {
+            Ok(metadata) => {
+                let modified = metadata.modified().map(iso8601).unwrap_or_else(|_| "unknown".to_string());
+                println!("Watching {} - {} bytes, modified {} (Ctrl+C to exit)\n", path, metadata.len(), modified);
+                if let Err(e) = do_read(path, offset, size, mode, None) {
+                    println!("Error reading file: {}", e);
+                }
+            }
+            Err(e) => println!("Watching {} - unavailable: {} (Ctrl+C to exit)", path, e),
+        }
+        stdout.flush()?;
+
+        while !watcher.poll() {
+            thread::sleep(interval);
+        }
+    }
+}
+
+// ==========================================
+// EDITEUR INTERACTIF (crossterm)
+// ==========================================
+
+/// État de l'éditeur plein écran
+struct HexEditor {
+    path: String,
+    /// Contenu affiché (modifications comprises)
+    data: Vec<u8>,
+    /// Modifications en attente, écrites seulement sur 'w'
+    dirty: Vec<(u64, u8)>,
+    cursor: usize,
+    top_row: usize,
+    /// Chiffres hex saisis en mode édition (None = mode navigation)
+    edit: Option<String>,
+    status: String,
+    quit_armed: bool,
+}
+
+impl HexEditor {
+    fn new(path: &str, data: Vec<u8>) -> Self {
+        HexEditor {
+            path: path.to_string(),
+            data,
+            dirty: Vec::new(),
+            cursor: 0,
+            top_row: 0,
+            edit: None,
+            status: "arrows: move  PgUp/PgDn: scroll  e: edit  w: save  q: quit".to_string(),
+            quit_armed: false,
+        }
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        if self.data.is_empty() {
+            return;
+        }
+        let target = self.cursor as isize + delta;
+        self.cursor = target.clamp(0, self.data.len() as isize - 1) as usize;
+    }
+
+    /// Garde le curseur visible dans une fenêtre de `rows` lignes
+    fn scroll_into_view(&mut self, rows: usize) {
+        let row = self.cursor / 16;
+        if row < self.top_row {
+            self.top_row = row;
+        } else if row >= self.top_row + rows {
+            self.top_row = row + 1 - rows;
+        }
+    }
+
+    /// Traite une touche, retourne false pour quitter
+    fn handle_key(&mut self, code: KeyCode, rows: usize) -> io::Result<bool> {
+        // Mode édition : deux chiffres hex remplacent l'octet courant
+        if let Some(digits) = &mut self.edit {
+            match code {
+                KeyCode::Esc => {
+                    self.edit = None;
+                    self.status = "edit cancelled".to_string();
+                }
+                KeyCode::Char(c) if c.is_ascii_hexdigit() => {
+                    digits.push(c);
+                    if digits.len() == 2 {
+                        let byte = u8::from_str_radix(digits, 16).unwrap_or(0);
+                        self.data[self.cursor] = byte;
+                        self.dirty.push((self.cursor as u64, byte));
+                        self.edit = None;
+                        self.status = format!("{:#010x} := {:02x}", self.cursor, byte);
+                        self.move_cursor(1);
+                    }
+                }
+                _ => {}
+            }
+            return Ok(true);
+        }
+
+        if code != KeyCode::Char('q') {
+            self.quit_armed = false;
+        }
+
+        match code {
+            KeyCode::Left => self.move_cursor(-1),
+            KeyCode::Right => self.move_cursor(1),
+            KeyCode::Up => self.move_cursor(-16),
+            KeyCode::Down => self.move_cursor(16),
+            KeyCode::PageUp => self.move_cursor(-16 * rows as isize),
+            KeyCode::PageDown => self.move_cursor(16 * rows as isize),
+            KeyCode::Char('e') if !self.data.is_empty() => {
+                self.edit = Some(String::new());
+                self.status = "edit: type two hex digits (Esc to cancel)".to_string();
+            }
+            KeyCode::Char('w') => self.save()?,
+            KeyCode::Char('q') => {
+                if self.dirty.is_empty() || self.quit_armed {
+                    return Ok(false);
+                }
+                self.quit_armed = true;
+                self.status = "unsaved changes: press q again to quit without saving".to_string();
+            }
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    /// Écrit les modifications via do_write, regroupées en plages contiguës
+    fn save(&mut self) -> io::Result<()> {
+        if self.dirty.is_empty() {
+            self.status = "nothing to save".to_string();
+            return Ok(());
+        }
+
+        // Dernière valeur par offset, triée par offset
+        let mut latest: Vec<(u64, u8)> = Vec::new();
+        let mut sorted = self.dirty.clone();
+        sorted.sort_by_key(|&(offset, _)| offset);
+        for (offset, byte) in sorted {
+            match latest.last_mut() {
+                Some(last) if last.0 == offset => last.1 = byte,
+                _ => latest.push((offset, byte)),
+            }
+        }
+
+        let mut runs: Vec<(u64, String)> = Vec::new();
+        for (offset, byte) in latest {
+            match runs.last_mut() {
+                Some((start, hex)) if *start + (hex.len() / 2) as u64 == offset => {
+                    hex.push_str(&format!("{:02x}", byte));
+                }
+                _ => runs.push((offset, format!("{:02x}", byte))),
+            }
+        }
+
+        // do_write affiche son feedback : on le laisse sur l'écran principal
+        let mut stdout = io::stdout();
+        execute!(stdout, LeaveAlternateScreen)?;
+        terminal::disable_raw_mode()?;
+        let mut result = Ok(());
+        for (offset, hex) in &runs {
+            if let Err(e) = do_write(&self.path, *offset, hex, DisplayMode::Hex, false, None) {
+                result = Err(e);
+                break;
+            }
+        }
+        terminal::enable_raw_mode()?;
+        execute!(stdout, EnterAlternateScreen)?;
+
+        match result {
+            Ok(()) => {
+                self.status = format!("saved {} byte(s) in {} write(s)", self.dirty.len(), runs.len());
+                self.dirty.clear();
+            }
+            Err(e) => self.status = format!("save failed: {}", e),
+        }
+        Ok(())
+    }
+
+    fn render(&self, out: &mut impl Write, rows: usize) -> io::Result<()> {
+        queue!(out, MoveTo(0, 0), Clear(ClearType::All))?;
+
+        for screen_row in 0..rows {
+            let row = self.top_row + screen_row;
+            let start = row * 16;
+            if start >= self.data.len() && !(start == 0 && self.data.is_empty()) {
+                break;
+            }
+            let chunk = &self.data[start..(start + 16).min(self.data.len())];
+
+            queue!(out, MoveTo(0, screen_row as u16), Print(format!("{:08x}: ", start)))?;
+
+            // Panneau hex
+            for (i, byte) in chunk.iter().enumerate() {
+                let text = if start + i == self.cursor {
+                    match &self.edit {
+                        Some(digits) => format!("{:_<2}", digits),
+                        None => format!("{:02x}", byte),
+                    }
+                } else {
+                    format!("{:02x}", byte)
+                };
+                self.print_cell(out, start + i, &text)?;
+                queue!(out, Print(" "))?;
+            }
+            queue!(out, Print("   ".repeat(16 - chunk.len())), Print("|"))?;
+
+            // Panneau ASCII
+            for (i, byte) in chunk.iter().enumerate() {
+                let c = if *byte >= 0x20 && *byte <= 0x7E { *byte as char } else { '.' };
+                self.print_cell(out, start + i, &c.to_string())?;
+            }
+            queue!(out, Print("|"))?;
+        }
+
+        let dirty_mark = if self.dirty.is_empty() { "" } else { " [modified]" };
+        queue!(
+            out,
+            MoveTo(0, rows as u16),
+            Print(format!(
+                "{}{} - offset {:#010x} / {} bytes",
+                self.path,
+                dirty_mark,
+                self.cursor,
+                self.data.len()
+            )),
+            MoveTo(0, rows as u16 + 1),
+            Print(&self.status),
+        )?;
+        out.flush()
+    }
+
+    /// Affiche une cellule, en vidéo inverse si c'est la position du curseur
+    fn print_cell(&self, out: &mut impl Write, index: usize, text: &str) -> io::Result<()> {
+        if index == self.cursor {
+            queue!(
+                out,
+                SetAttribute(Attribute::Reverse),
+                Print(text),
+                SetAttribute(Attribute::Reset)
+            )
+        } else {
+            queue!(out, Print(text))
+        }
+    }
+}
+
+/// Lance l'éditeur plein écran sur le fichier
+fn run_interactive(path: &str) -> io::Result<()> {
+    let data = read_range(path, 0, None)?;
+    let mut editor = HexEditor::new(path, data);
+
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(stdout, EnterAlternateScreen, Hide)?;
+
+    let result = (|| -> io::Result<()> {
+        loop {
+            // Deux lignes réservées en bas pour l'état
+            let (_, height) = terminal::size()?;
+            let rows = (height as usize).saturating_sub(2).max(1);
+            editor.scroll_into_view(rows);
+            editor.render(&mut stdout, rows)?;
+
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && !editor.handle_key(key.code, rows)?
+            {
+                return Ok(());
+            }
+        }
+    })();
+
+    // On restaure toujours le terminal, même en cas d'erreur
+    execute!(stdout, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+// ==========================================
+// JOURNAL D'ANNULATION (--undo / --redo)
+// ==========================================
+
+fn journal_path(path: &str) -> String {
+    format!("{}.hexjournal", path)
+}
+
+/// Les entrées annulées sont gardées à part pour --redo
+fn redo_path(path: &str) -> String {
+    format!("{}.hexjournal.redo", path)
+}
+
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn read_journal(path: &str) -> Result<Vec<JournalEntry>, HexEditorError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .map_err(|e| HexEditorError::ParseError(format!("Corrupt journal {}: {}", path, e)))
+        })
+        .collect()
+}
+
+/// Réécrit le journal en ne gardant que les `limit` entrées les plus récentes
+fn write_journal(path: &str, entries: &[JournalEntry], limit: usize) -> Result<(), HexEditorError> {
+    let kept = &entries[entries.len().saturating_sub(limit)..];
+    if kept.is_empty() {
+        return match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        };
+    }
+    let mut content = String::new();
+    for entry in kept {
+        content.push_str(&serde_json::to_string(entry).map_err(|e| HexEditorError::ParseError(e.to_string()))?);
+        content.push('\n');
+    }
+    Ok(fs::write(path, content)?)
+}
+
+/// Exécute une écriture de `len` octets à `offset` et l'enregistre dans le journal
+fn with_journal<F>(path: &str, offset: u64, len: u64, limit: usize, op: F) -> Result<(), HexEditorError>
+where
+    F: FnOnce() -> Result<(), HexEditorError>,
+{
+    // Instantané avant écriture (fichier éventuellement absent)
+    let file_len_before = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let original = read_range(path, offset, Some(len)).unwrap_or_default();
+
+    op()?;
+
+    let written = read_range(path, offset, Some(len))?;
+    push_journal_entry(path, JournalOp::Overwrite, offset, &original, &written, file_len_before, limit)
+}
+
+/// Ajoute une entrée au journal de `path` et vide l'historique de rétablissement
+fn push_journal_entry(
+    path: &str,
+    op: JournalOp,
+    offset: u64,
+    original: &[u8],
+    written: &[u8],
+    file_len_before: u64,
+    limit: usize,
+) -> Result<(), HexEditorError> {
+    let timestamp_unix = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let journal = journal_path(path);
+    let mut entries = read_journal(&journal)?;
+    entries.push(JournalEntry {
+        offset,
+        original_bytes_hex: bytes_to_hex(original),
+        written_bytes_hex: bytes_to_hex(written),
+        timestamp_unix,
+        file_len_before: Some(file_len_before),
+        op,
+    });
+    write_journal(&journal, &entries, limit)?;
+
+    // Une nouvelle écriture invalide l'historique de rétablissement
+    write_journal(&redo_path(path), &[], limit)
+}
+
+/// Retire `remove` octets à `offset` puis y insère `insert` (fichier réécrit en entier)
+fn splice_file(path: &str, offset: u64, remove: usize, insert: &[u8]) -> Result<(), HexEditorError> {
+    let mut data = fs::read(path)?;
+    let start = (offset as usize).min(data.len());
+    let end = (start + remove).min(data.len());
+    data.splice(start..end, insert.iter().copied());
+    Ok(replace_file(path, &data)?)
+}
+
+/// Réécrit les octets d'origine d'une entrée (et retire une éventuelle extension)
+fn apply_undo(path: &str, entry: &JournalEntry) -> Result<(), HexEditorError> {
+    let original = hex_string_to_bytes(&entry.original_bytes_hex)?;
+    match entry.op {
+        JournalOp::Insert => return splice_file(path, entry.offset, entry.written_bytes_hex.len() / 2, &[]),
+        JournalOp::Delete => return splice_file(path, entry.offset, 0, &original),
+        JournalOp::Overwrite => {}
+    }
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    file.seek(SeekFrom::Start(entry.offset))?;
+    file.write_all(&original)?;
+
+    if let Some(len_before) = entry.file_len_before {
+        let current = file.metadata()?.len();
+        if current > len_before {
+            file.set_len(len_before)?;
+        }
+    }
+    Ok(())
+}
+
+/// Réapplique les octets écrits d'une entrée
+fn apply_redo(path: &str, entry: &JournalEntry) -> Result<(), HexEditorError> {
+    let written = hex_string_to_bytes(&entry.written_bytes_hex)?;
+    match entry.op {
+        JournalOp::Insert => return splice_file(path, entry.offset, 0, &written),
+        JournalOp::Delete => return splice_file(path, entry.offset, entry.original_bytes_hex.len() / 2, &[]),
+        JournalOp::Overwrite => {}
+    }
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    file.seek(SeekFrom::Start(entry.offset))?;
+    Ok(file.write_all(&written)?)
+}
+
+fn do_undo(path: &str, limit: usize) -> Result<(), HexEditorError> {
+    let journal = journal_path(path);
+    let mut entries = read_journal(&journal)?;
+    let entry = entries.pop().ok_or(HexEditorError::NothingToUndo)?;
+
+    apply_undo(path, &entry)?;
+    write_journal(&journal, &entries, limit)?;
+
+    let redo = redo_path(path);
+    let mut undone = read_journal(&redo)?;
+    undone.push(entry.clone());
+    write_journal(&redo, &undone, limit)?;
+
+    let (action, len) = match entry.op {
+        JournalOp::Insert => ("removed inserted", entry.written_bytes_hex.len() / 2),
+        JournalOp::Delete => ("restored deleted", entry.original_bytes_hex.len() / 2),
+        JournalOp::Overwrite => ("restored", entry.original_bytes_hex.len() / 2),
+    };
+    println!("undo: {} {} bytes at offset {:#010x}", action, len, entry.offset);
+    println!("✓ {} operation(s) left in journal", entries.len());
+    Ok(())
+}
+
+fn do_redo(path: &str, limit: usize) -> Result<(), HexEditorError> {
+    let redo = redo_path(path);
+    let mut undone = read_journal(&redo)?;
+    let entry = undone.pop().ok_or(HexEditorError::NothingToRedo)?;
+
+    apply_redo(path, &entry)?;
+    write_journal(&redo, &undone, limit)?;
+
+    let journal = journal_path(path);
+    let mut entries = read_journal(&journal)?;
+    entries.push(entry.clone());
+    write_journal(&journal, &entries, limit)?;
+
+    let (action, len) = match entry.op {
+        JournalOp::Insert => ("re-inserted", entry.written_bytes_hex.len() / 2),
+        JournalOp::Delete => ("deleted again", entry.original_bytes_hex.len() / 2),
+        JournalOp::Overwrite => ("rewrote", entry.written_bytes_hex.len() / 2),
+    };
+    println!("redo: {} {} bytes at offset {:#010x}", action, len, entry.offset);
+    println!("✓ {} operation(s) left to redo", undone.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fichier temporaire contenant `content`
+    fn temp_with(content: &[u8]) -> NamedTempFile {
+        let mut temp = NamedTempFile::new().unwrap();
+        temp.write_all(content).unwrap();
+        temp
+    }
+
+    #[test]
+    fn bad_offset_is_invalid_offset() {
+        assert!(matches!(parse_offset("12a"), Err(HexEditorError::InvalidOffset(input)) if input == "12a"));
+        assert!(matches!(parse_offset("0xZZ"), Err(HexEditorError::InvalidOffset(input)) if input == "0xZZ"));
+        assert_eq!(parse_offset("0x10").unwrap(), 16);
+    }
+
+    #[test]
+    fn odd_length_hex_is_parse_error() {
+        assert!(matches!(hex_string_to_bytes("abc"), Err(HexEditorError::ParseError(_))));
+        assert!(matches!(hex_string_to_bytes("zz"), Err(HexEditorError::ParseError(_))));
+    }
+
+    #[test]
+    fn multibyte_hex_is_parse_error() {
+        // "é" fait 2 octets : longueur paire, mais la première paire coupe le caractère
+        assert!(matches!(hex_string_to_bytes("aé0"), Err(HexEditorError::ParseError(_))));
+    }
+
+    #[test]
+    fn fill_past_eof_is_out_of_bounds() {
+        let temp = temp_with(b"abcd");
+        let path = temp.path().to_str().unwrap();
+        let result = do_fill(path, 2, 4, &[0xff], false, DisplayMode::Hex, false);
+        assert!(matches!(result, Err(HexEditorError::OutOfBounds { offset: 2, end: 6, file_len: 4 })));
+        assert_eq!(fs::read(path).unwrap(), b"abcd");
+    }
+
+    #[test]
+    fn empty_journal_is_nothing_to_undo() {
+        let temp = temp_with(b"abcd");
+        let path = temp.path().to_str().unwrap();
+        assert!(matches!(do_undo(path, 100), Err(HexEditorError::NothingToUndo)));
+        assert!(matches!(do_redo(path, 100), Err(HexEditorError::NothingToRedo)));
+    }
+
+    #[test]
+    fn dry_run_write_does_not_create_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing.bin");
+        let path = path.to_str().unwrap();
+        do_write(path, 0, "48656c6c6f", DisplayMode::Hex, true, None).unwrap();
+        assert!(!Path::new(path).exists());
+    }
+
+    #[test]
+    fn malformed_patch_lines_are_counted_as_skipped() {
+        let (patches, skipped) = parse_patch_file("# en-tête\n0x00 41\n10 zz\n0x02 4243\n", false).unwrap();
+        assert_eq!(patches, vec![(0, vec![0x41]), (2, vec![0x42, 0x43])]);
+        assert_eq!(skipped, 1);
+        assert!(parse_patch_file("10 zz\n", true).is_err());
+    }
+
+    #[test]
+    fn diff_cells_are_plain_without_color() {
+        let plain = format_hex_cells(&[0x41, 0x42], &[0x41, 0x43], false);
+        assert!(!plain.contains('\x1b'));
+        assert!(plain.starts_with("41 42 "));
+        assert!(format_hex_cells(&[0x41, 0x42], &[0x41, 0x43], true).contains("\x1b[31m42\x1b[0m"));
+    }
+}
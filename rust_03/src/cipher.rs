@@ -0,0 +1,233 @@
+// ==========================================
+// CHIFFREMENTS PAR FLOT
+// ==========================================
+
+// Paramètres LCG
+const LCG_A: u32 = 1103515245;
+const LCG_C: u32 = 12345;
+
+/// Chiffrement par flot : XOR des données avec un keystream
+pub trait StreamCipher: Send {
+    /// Octet suivant du keystream
+    fn next_byte(&mut self) -> u8;
+
+    /// Position courante dans le keystream (octets déjà consommés)
+    fn position(&self) -> usize;
+
+    /// Chiffre ou déchiffre (XOR) sans rien afficher (gros volumes : fichiers)
+    fn apply(&mut self, data: &[u8]) -> Vec<u8> {
+        data.iter().map(|&b| b ^ self.next_byte()).collect()
+    }
+
+    /// Chiffre ou déchiffre (XOR) en affichant le détail de l'opération
+    fn process(&mut self, data: &[u8], mode: &str) -> Vec<u8> {
+        let start_pos = self.position();
+        let mut out = Vec::new();
+        let mut key_bytes = Vec::new();
+
+        for &b in data {
+            let k = self.next_byte();
+            key_bytes.push(k);
+            out.push(b ^ k);
+        }
+
+        println!("[{}]", mode);
+        if mode == "ENCRYPT" {
+             print!("Plain: ");
+             for b in data { print!("{:02x} ", b); }
+             if let Ok(s) = std::str::from_utf8(data) { print!("({:?})", s); }
+             println!();
+        } else {
+             print!("Cipher: ");
+             for b in data { print!("{:02x} ", b); }
+             println!();
+        }
+
+        print!("Key: ");
+        for k in &key_bytes { print!("{:02x} ", k); }
+        println!(" (keystream position: {})", start_pos);
+
+        if mode == "ENCRYPT" {
+            print!("Cipher: ");
+            for b in &out { print!("{:02x} ", b); }
+            println!();
+        } else {
+            print!("Plain: ");
+            for b in &out { print!("{:02x} ", b); }
+            if let Ok(s) = std::str::from_utf8(&out) { print!(" -> {:?}", s); }
+            println!();
+        }
+        println!(); 
+
+        out
+    }
+}
+
+// ------------------------------------------
+// LCG (historique, --legacy)
+// ------------------------------------------
+
+pub struct LcgCipher {
+    state: u32,
+    count: usize,
+}
+
+impl LcgCipher {
+    pub fn new(seed: u64) -> Self {
+        println!("[STREAM] Generating keystream from secret...");
+        println!("Algorithm: LCG (a={}, c={}, m=2^32)", LCG_A, LCG_C);
+        println!("Seed: secret = {:X}", seed);
+        
+        let state = seed as u32;
+        
+        print!("\nKeystream: ");
+        let mut temp_state = state;
+        for _ in 0..10 {
+            temp_state = temp_state.wrapping_mul(LCG_A).wrapping_add(LCG_C);
+            let byte = (temp_state >> 24) as u8;
+            print!("{:02X} ", byte);
+        }
+        println!("... \n");
+
+        LcgCipher { state, count: 0 }
+    }
+}
+
+impl StreamCipher for LcgCipher {
+    fn next_byte(&mut self) -> u8 {
+        self.state = self.state.wrapping_mul(LCG_A).wrapping_add(LCG_C);
+        self.count += 1;
+        (self.state >> 24) as u8 
+    }
+
+    fn position(&self) -> usize {
+        self.count
+    }
+}
+
+// ------------------------------------------
+// ChaCha20 (RFC 7539)
+// ------------------------------------------
+
+/// "expand 32-byte k" en little-endian
+const CHACHA_CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+pub struct ChaCha20Cipher {
+    /// État initial : constantes, clé, compteur, nonce
+    state: [u32; 16],
+    /// Bloc de keystream courant (64 octets)
+    block: [u8; 64],
+    /// Position dans le bloc courant (64 = bloc épuisé)
+    block_pos: usize,
+    count: usize,
+}
+
+impl ChaCha20Cipher {
+    pub fn new(key: &[u8; 32], nonce: &[u8; 12], counter: u32) -> Self {
+        let mut state = [0u32; 16];
+        state[..4].copy_from_slice(&CHACHA_CONSTANTS);
+        for i in 0..8 {
+            state[4 + i] = u32::from_le_bytes(key[i * 4..i * 4 + 4].try_into().unwrap());
+        }
+        state[12] = counter;
+        for i in 0..3 {
+            state[13 + i] = u32::from_le_bytes(nonce[i * 4..i * 4 + 4].try_into().unwrap());
+        }
+
+        ChaCha20Cipher { state, block: [0; 64], block_pos: 64, count: 0 }
+    }
+
+    /// Affiche les paramètres de session comme le fait LcgCipher::new
+    pub fn announce(key: &[u8; 32], nonce: &[u8; 12]) {
+        println!("[STREAM] Generating keystream from secret...");
+        println!("Algorithm: ChaCha20 (RFC 7539, 20 rounds)");
+        print!("Key: SHA-256(secret) = ");
+        for b in key { print!("{:02X}", b); }
+        println!();
+        print!("Nonce: ");
+        for b in nonce { print!("{:02X}", b); }
+        println!("\n");
+    }
+
+    fn quarter_round(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+        s[a] = s[a].wrapping_add(s[b]); s[d] ^= s[a]; s[d] = s[d].rotate_left(16);
+        s[c] = s[c].wrapping_add(s[d]); s[b] ^= s[c]; s[b] = s[b].rotate_left(12);
+        s[a] = s[a].wrapping_add(s[b]); s[d] ^= s[a]; s[d] = s[d].rotate_left(8);
+        s[c] = s[c].wrapping_add(s[d]); s[b] ^= s[c]; s[b] = s[b].rotate_left(7);
+    }
+
+    /// Fonction de bloc : 20 rounds (10 doubles rounds colonnes + diagonales)
+    fn chacha_block(state: &[u32; 16]) -> [u8; 64] {
+        let mut working = *state;
+        for _ in 0..10 {
+            Self::quarter_round(&mut working, 0, 4, 8, 12);
+            Self::quarter_round(&mut working, 1, 5, 9, 13);
+            Self::quarter_round(&mut working, 2, 6, 10, 14);
+            Self::quarter_round(&mut working, 3, 7, 11, 15);
+            Self::quarter_round(&mut working, 0, 5, 10, 15);
+            Self::quarter_round(&mut working, 1, 6, 11, 12);
+            Self::quarter_round(&mut working, 2, 7, 8, 13);
+            Self::quarter_round(&mut working, 3, 4, 9, 14);
+        }
+
+        let mut out = [0u8; 64];
+        for (i, (w, s)) in working.iter().zip(state.iter()).enumerate() {
+            out[i * 4..i * 4 + 4].copy_from_slice(&w.wrapping_add(*s).to_le_bytes());
+        }
+        out
+    }
+}
+
+impl StreamCipher for ChaCha20Cipher {
+    fn next_byte(&mut self) -> u8 {
+        if self.block_pos == 64 {
+            self.block = Self::chacha_block(&self.state);
+            self.state[12] = self.state[12].wrapping_add(1);
+            self.block_pos = 0;
+        }
+        let byte = self.block[self.block_pos];
+        self.block_pos += 1;
+        self.count += 1;
+        byte
+    }
+
+    fn position(&self) -> usize {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Vecteur de test RFC 7539 §2.4.2 (clé 00..1f, compteur initial 1)
+    const KEY: [u8; 32] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+        0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f,
+    ];
+    const NONCE: [u8; 12] = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00];
+    const PLAINTEXT: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+    const CIPHERTEXT: [u8; 114] = [
+        0x6e, 0x2e, 0x35, 0x9a, 0x25, 0x68, 0xf9, 0x80, 0x41, 0xba, 0x07, 0x28, 0xdd, 0x0d, 0x69, 0x81,
+        0xe9, 0x7e, 0x7a, 0xec, 0x1d, 0x43, 0x60, 0xc2, 0x0a, 0x27, 0xaf, 0xcc, 0xfd, 0x9f, 0xae, 0x0b,
+        0xf9, 0x1b, 0x65, 0xc5, 0x52, 0x47, 0x33, 0xab, 0x8f, 0x59, 0x3d, 0xab, 0xcd, 0x62, 0xb3, 0x57,
+        0x16, 0x39, 0xd6, 0x24, 0xe6, 0x51, 0x52, 0xab, 0x8f, 0x53, 0x0c, 0x35, 0x9f, 0x08, 0x61, 0xd8,
+        0x07, 0xca, 0x0d, 0xbf, 0x50, 0x0d, 0x6a, 0x61, 0x56, 0xa3, 0x8e, 0x08, 0x8a, 0x22, 0xb6, 0x5e,
+        0x52, 0xbc, 0x51, 0x4d, 0x16, 0xcc, 0xf8, 0x06, 0x81, 0x8c, 0xe9, 0x1a, 0xb7, 0x79, 0x37, 0x36,
+        0x5a, 0xf9, 0x0b, 0xbf, 0x74, 0xa3, 0x5b, 0xe6, 0xb4, 0x0b, 0x8e, 0xed, 0xf2, 0x78, 0x5e, 0x42,
+        0x87, 0x4d,
+    ];
+
+    #[test]
+    fn chacha20_matches_rfc7539_vector() {
+        let cipher = ChaCha20Cipher::new(&KEY, &NONCE, 1).apply(PLAINTEXT);
+        assert_eq!(cipher, CIPHERTEXT);
+    }
+
+    #[test]
+    fn chacha20_round_trip() {
+        let encrypted = ChaCha20Cipher::new(&KEY, &NONCE, 1).apply(PLAINTEXT);
+        let decrypted = ChaCha20Cipher::new(&KEY, &NONCE, 1).apply(&encrypted);
+        assert_eq!(decrypted, PLAINTEXT);
+    }
+}
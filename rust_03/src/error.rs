@@ -0,0 +1,63 @@
+// ==========================================
+// ERREURS
+// ==========================================
+
+use std::fmt;
+use std::io;
+
+/// Erreurs de connexion et de session, à filtrer par variante côté appelant
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ChatError {
+    /// Erreur réseau ou fichier
+    IoError(io::Error),
+    /// Valeur mal formée (option hexadécimale, trame de contrôle)
+    ParseError(String),
+    /// Paramètres DH refusés (P pair ou non premier, G hors bornes)
+    InvalidParameters(String),
+    /// Connexion refusée par le serveur (serveur plein)
+    ConnectionRefused(String),
+    /// Tag HMAC invalide sur une trame de contrôle
+    AuthenticationFailed,
+    /// Jeton --password du pair incorrect (ou absent)
+    PeerRejected { peer: &'static str },
+    /// Le pair n'a pas acquitté le rekey à temps
+    RekeyTimeout,
+    /// Fichier à envoyer illisible
+    FileError { path: String, source: io::Error },
+    /// Fichier au-delà de --max-file-size
+    FileTooLarge { size: u64, max: u64 },
+}
+
+impl fmt::Display for ChatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChatError::IoError(e) => write!(f, "{}", e),
+            ChatError::ParseError(msg) => write!(f, "{}", msg),
+            ChatError::InvalidParameters(msg) => write!(f, "invalid DH parameters: {}", msg),
+            ChatError::ConnectionRefused(reason) => write!(f, "connection refused by server: {}", reason),
+            ChatError::AuthenticationFailed => write!(f, "message authentication failed"),
+            ChatError::PeerRejected { peer } => write!(f, "{} authentication failed", peer),
+            ChatError::RekeyTimeout => write!(f, "peer did not acknowledge rekey within 5 seconds"),
+            ChatError::FileError { path, source } => write!(f, "{}: {}", path, source),
+            ChatError::FileTooLarge { size, max } => {
+                write!(f, "file is {} bytes (--max-file-size {})", size, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChatError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ChatError::IoError(e) | ChatError::FileError { source: e, .. } => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ChatError {
+    fn from(e: io::Error) -> Self {
+        ChatError::IoError(e)
+    }
+}
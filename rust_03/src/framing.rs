@@ -0,0 +1,42 @@
+// ==========================================
+// TRAMAGE (LENGTH-PREFIX)
+// ==========================================
+
+use std::io::{self, Read, Write};
+
+/// Taille maximale d'une trame (ciphertext + HMAC) : 1 MiB
+pub const MAX_FRAME_SIZE: usize = 1024 * 1024;
+
+/// Envoie une trame : longueur sur 4 octets big-endian puis les données
+pub fn send_framed<W: Write + ?Sized>(stream: &mut W, data: &[u8]) -> io::Result<()> {
+    if data.len() > MAX_FRAME_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("frame too large ({} bytes, max {})", data.len(), MAX_FRAME_SIZE),
+        ));
+    }
+
+    let mut frame = Vec::with_capacity(4 + data.len());
+    frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    frame.extend_from_slice(data);
+    stream.write_all(&frame)
+}
+
+/// Reçoit une trame complète, quelle que soit la fragmentation TCP
+/// Une longueur annoncée supérieure à MAX_FRAME_SIZE est une erreur (InvalidData)
+pub fn recv_framed<R: Read + ?Sized>(stream: &mut R) -> io::Result<Vec<u8>> {
+    let mut len_bytes = [0u8; 4];
+    stream.read_exact(&mut len_bytes)?;
+    let len = u32::from_be_bytes(len_bytes) as usize;
+
+    if len > MAX_FRAME_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame too large ({} bytes, max {})", len, MAX_FRAME_SIZE),
+        ));
+    }
+
+    let mut data = vec![0u8; len];
+    stream.read_exact(&mut data)?;
+    Ok(data)
+}
@@ -0,0 +1,71 @@
+// ==========================================
+// JOURNAL DES MESSAGES (--log)
+// ==========================================
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Sens d'un message journalisé
+#[derive(Clone, Copy)]
+pub enum LogDirection {
+    Sent,
+    Recv,
+}
+
+/// Journal en ajout : une ligne `[horodatage] [SENT|RECV] message` par message,
+/// vidée sur disque après chaque écriture
+pub struct Logger {
+    out: BufWriter<File>,
+    /// --log-encrypt : ciphertext en hex + position du keystream au lieu du clair
+    encrypted: bool,
+}
+
+impl Logger {
+    pub fn open(path: &Path, encrypted: bool) -> io::Result<Self> {
+        let file = OpenOptions::new().append(true).create(true).open(path)?;
+        Ok(Logger { out: BufWriter::new(file), encrypted })
+    }
+
+    /// `position` : position du keystream au premier octet du message,
+    /// suffisante (avec la clé de session) pour le déchiffrer indépendamment
+    pub fn record(&mut self, direction: LogDirection, plain: &[u8], ciphertext: &[u8], position: usize) -> io::Result<()> {
+        let tag = match direction {
+            LogDirection::Sent => "SENT",
+            LogDirection::Recv => "RECV",
+        };
+        write!(self.out, "[{}] [{}] ", iso8601_now(), tag)?;
+        if self.encrypted {
+            for b in ciphertext {
+                write!(self.out, "{:02x}", b)?;
+            }
+            writeln!(self.out, " (keystream position: {})", position)?;
+        } else {
+            writeln!(self.out, "{}", String::from_utf8_lossy(plain))?;
+        }
+        self.out.flush()
+    }
+}
+
+/// Horodatage UTC au format ISO 8601 (ex. 2024-05-01T12:34:56Z)
+fn iso8601_now() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+
+    // Jours depuis 1970-01-01 -> date civile (algorithme de H. Hinnant)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, rem / 3600, rem % 3600 / 60, rem % 60
+    )
+}
@@ -1,20 +1,91 @@
-use clap::{Parser, Subcommand};
+mod cipher;
+mod error;
+mod framing;
+mod logger;
+mod tls;
+mod transport;
+
+use cipher::{ChaCha20Cipher, LcgCipher, StreamCipher};
+use error::ChatError;
+use framing::{recv_framed, send_framed};
+use logger::{LogDirection, Logger};
+use transport::{TcpTransport, Transport, UnixTransport};
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
 use rand::Rng; // Nécessaire pour le trait .random()
+use std::fs::{self, File, OpenOptions};
 use std::io::{self, Read, Write};
-use std::net::{TcpListener, TcpStream};
+use socket2::{Domain, Protocol, Socket, Type};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::thread;
+use std::time::{Duration, Instant};
 use std::process; // Pour exit(1)
 
 // ==========================================
 // 1. CONSTANTES & CONFIGURATION
 // ==========================================
 
-const P: u64 = 0xD87FA3E291B4C7F3; // Safe prime (64-bit)
+// Paramètres DH par défaut du serveur (surchargeables avec --prime / --generator)
+const P: u64 = 0xD87FA3E291B4D963; // Safe prime (64-bit)
 const G: u64 = 2;                  // Generator
 
-// Paramètres LCG
-const LCG_A: u32 = 1103515245;
-const LCG_C: u32 = 12345;
+// Nombre de tours Miller-Rabin pour vérifier le P reçu du serveur
+const MILLER_RABIN_ROUNDS: u8 = 5;
+
+// Octet d'accueil envoyé par le serveur juste après accept()
+const STATUS_ACCEPTED: u8 = 0x01;
+const STATUS_SERVER_FULL: u8 = 0x00;
+
+// Les fichiers sont découpés en morceaux pour respecter la taille max d'une trame
+const FILE_CHUNK_SIZE: usize = 256 * 1024;
+
+// Délai maximal d'attente de l'acquittement d'un rekey
+const REKEY_ACK_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Délai de réponse à un /ping avant de fermer la connexion
+const PONG_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Nombre d'allers-retours mesurés par --latency
+const LATENCY_SAMPLES: usize = 100;
+
+/// Type de message : premier octet de chaque trame (en clair, couvert par le HMAC)
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MessageType {
+    Text = 0x01,
+    File = 0x02,
+    Rekey = 0x03,
+    RekeyAck = 0x04,
+    Ping = 0x05,
+    Pong = 0x06,
+    LatencyPing = 0x07,
+    LatencyEcho = 0x08,
+}
+
+impl MessageType {
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0x01 => Some(MessageType::Text),
+            0x02 => Some(MessageType::File),
+            0x03 => Some(MessageType::Rekey),
+            0x04 => Some(MessageType::RekeyAck),
+            0x05 => Some(MessageType::Ping),
+            0x06 => Some(MessageType::Pong),
+            0x07 => Some(MessageType::LatencyPing),
+            0x08 => Some(MessageType::LatencyEcho),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Parser)]
 #[command(name = "streamchat")]
@@ -22,6 +93,61 @@ const LCG_C: u32 = 12345;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Use the legacy LCG keystream instead of ChaCha20 (both peers must agree)
+    #[arg(long, global = true)]
+    legacy: bool,
+
+    /// Largest file accepted or sent with /send, in bytes
+    #[arg(long, global = true, default_value_t = 10 * 1024 * 1024, conflicts_with = "tls")]
+    max_file_size: u64,
+
+    /// Renegotiate ephemeral DH keys after this many sent messages (0 disables)
+    #[arg(long, global = true, default_value_t = 100, conflicts_with = "tls")]
+    rekey_interval: u64,
+
+    /// Seconds without incoming data before the peer is pinged
+    #[arg(long, global = true, default_value_t = 300, value_parser = clap::value_parser!(u64).range(1..),
+          conflicts_with = "tls")]
+    timeout: u64,
+
+    /// Use a TLS 1.3 session (rustls, self-signed certificate) instead of DH + stream cipher
+    #[arg(long, global = true, conflicts_with = "legacy")]
+    tls: bool,
+
+    /// Shared password checked after the key exchange (both peers must use it; never sent)
+    #[arg(long, global = true, conflicts_with = "tls")]
+    password: Option<String>,
+
+    /// Measure round-trip latency with 100 encrypted pings before chatting (both peers must use it)
+    #[arg(long, global = true, conflicts_with = "tls")]
+    latency: bool,
+
+    /// Append every sent and received message to this file
+    #[arg(long, global = true, conflicts_with = "tls")]
+    log: Option<PathBuf>,
+
+    /// Log hex ciphertext and keystream position instead of plaintext
+    #[arg(long, global = true, requires = "log")]
+    log_encrypt: bool,
+
+    /// Do not print "[YOU] <message>" after each sent message
+    #[arg(long, global = true, conflicts_with = "tls")]
+    no_echo: bool,
+}
+
+/// Options de session communes au serveur et au client
+#[derive(Clone)]
+struct SessionOptions {
+    legacy: bool,
+    max_file_size: u64,
+    rekey_interval: u64,
+    timeout: u64,
+    password: Option<Arc<str>>,
+    latency: bool,
+    /// Journal partagé par toutes les connexions (--log)
+    log: Option<Arc<Mutex<Logger>>>,
+    renderer: ChatRenderer,
 }
 
 #[derive(Subcommand)]
@@ -30,10 +156,47 @@ enum Commands {
     Server {
         #[arg(default_value_t = 8080)]
         port: u16,
+
+        /// Maximum number of simultaneously connected clients
+        #[arg(long, default_value_t = 10)]
+        max_clients: usize,
+
+        /// DH prime modulus sent to clients, as 64-bit hex [default: built-in safe prime]
+        #[arg(long, value_parser = parse_hex_u64, conflicts_with = "tls")]
+        prime: Option<u64>,
+
+        /// DH generator sent to clients
+        #[arg(long, default_value_t = G, conflicts_with = "tls")]
+        generator: u64,
+
+        /// Relay every client's messages to all other clients (server decrypts and re-encrypts)
+        #[arg(long, conflicts_with = "tls")]
+        group: bool,
+
+        /// Append relayed plaintext messages to this file (debugging)
+        #[arg(long, requires = "group")]
+        relay_log: Option<PathBuf>,
+
+        /// Listen on [::]:<port> (IPv6) instead of 0.0.0.0:<port>
+        #[arg(long)]
+        ipv6: bool,
+
+        /// Listen on both 0.0.0.0:<port> and [::]:<port>
+        #[arg(long, conflicts_with_all = ["ipv6", "tls"])]
+        dual_stack: bool,
+
+        /// Listen on a Unix domain socket at this path instead of TCP (local IPC)
+        #[arg(long, conflicts_with_all = ["ipv6", "dual_stack", "tls"])]
+        unix: Option<PathBuf>,
     },
     /// Connect to server
     Client {
-        host: String,
+        #[arg(required_unless_present = "unix")]
+        host: Option<String>,
+
+        /// Connect to a Unix domain socket at this path instead of TCP
+        #[arg(long, conflicts_with_all = ["host", "tls"])]
+        unix: Option<PathBuf>,
     },
 }
 
@@ -57,77 +220,178 @@ fn mod_pow(base: u64, exp: u64, modulus: u64) -> u64 {
     result as u64
 }
 
-struct LcgCipher {
-    state: u32,
-    count: usize,
-}
+/// Test de primalité probabiliste (bases aléatoires)
+fn miller_rabin(n: u64, rounds: u8) -> bool {
+    if n < 4 {
+        return n == 2 || n == 3;
+    }
+    if n.is_multiple_of(2) {
+        return false;
+    }
 
-impl LcgCipher {
-    fn new(seed: u64) -> Self {
-        println!("[STREAM] Generating keystream from secret...");
-        println!("Algorithm: LCG (a={}, c={}, m=2^32)", LCG_A, LCG_C);
-        println!("Seed: secret = {:X}", seed);
-        
-        let state = seed as u32;
-        
-        print!("\nKeystream: ");
-        let mut temp_state = state;
-        for _ in 0..10 {
-            temp_state = temp_state.wrapping_mul(LCG_A).wrapping_add(LCG_C);
-            let byte = (temp_state >> 24) as u8;
-            print!("{:02X} ", byte);
+    // n - 1 = d * 2^r avec d impair
+    let r = (n - 1).trailing_zeros();
+    let d = (n - 1) >> r;
+    let mut rng = rand::rng();
+
+    'witness: for _ in 0..rounds {
+        let a = rng.random_range(2..n - 1);
+        let mut x = mod_pow(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..r {
+            x = mod_pow(x, 2, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
         }
-        println!("... \n");
+        return false;
+    }
+    true
+}
 
-        LcgCipher { state, count: 0 }
+/// Jeton de confirmation du mot de passe : SHA-256(secret || mot de passe)
+/// Lié au secret DH de la session : rejouer un jeton d'une autre session ne sert à rien
+fn derive_auth_token(shared_secret: u64, password: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(shared_secret.to_be_bytes());
+    hasher.update(password.as_bytes());
+    hasher.finalize().into()
+}
+
+/// Le client prouve d'abord qu'il connaît le mot de passe, puis le serveur.
+/// Le serveur ne répond qu'après vérification : un client sans mot de passe ne peut
+/// pas simplement renvoyer le jeton reçu (réflexion).
+fn confirm_password(stream: &mut dyn Transport, shared_secret: u64, password: &str, is_server: bool) -> Result<(), ChatError> {
+    let ours = derive_auth_token(shared_secret, password);
+    let peer = if is_server { "Client" } else { "Server" };
+    let mut theirs = [0u8; 32];
+
+    println!("[AUTH] Confirming password: SHA-256(secret || password)");
+    if is_server {
+        stream.read_exact(&mut theirs)?;
+        if !tokens_match(&ours, &theirs) {
+            return Err(ChatError::PeerRejected { peer });
+        }
+        stream.write_all(&ours)?;
+    } else {
+        stream.write_all(&ours)?;
+        // Le serveur ferme la connexion sans répondre si notre jeton est faux
+        if stream.read_exact(&mut theirs).is_err() || !tokens_match(&ours, &theirs) {
+            return Err(ChatError::PeerRejected { peer });
+        }
     }
+    println!("[AUTH] {} knows the password ✓\n", peer);
+    Ok(())
+}
 
-    fn next_byte(&mut self) -> u8 {
-        self.state = self.state.wrapping_mul(LCG_A).wrapping_add(LCG_C);
-        self.count += 1;
-        (self.state >> 24) as u8 
+/// Comparaison en temps constant
+fn tokens_match(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Dérive deux clés indépendantes du secret DH : (chiffrement, authentification)
+/// - chiffrement     = SHA-256(secret)
+/// - authentification = SHA-256(secret || "auth")
+fn derive_keys(shared_secret: u64) -> (Vec<u8>, Vec<u8>) {
+    let encryption_key = Sha256::digest(shared_secret.to_be_bytes()).to_vec();
+
+    let mut hasher = Sha256::new();
+    hasher.update(shared_secret.to_be_bytes());
+    hasher.update(b"auth");
+    let auth_key = hasher.finalize().to_vec();
+
+    (encryption_key, auth_key)
+}
+
+/// Tag HMAC-SHA256 sur nonce || ciphertext (32 octets)
+fn compute_tag(auth_key: &[u8], nonce: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(auth_key).expect("HMAC accepts any key length");
+    mac.update(nonce);
+    mac.update(ciphertext);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Vérification du tag en temps constant
+fn verify_tag(auth_key: &[u8], nonce: &[u8], ciphertext: &[u8], tag: &[u8]) -> bool {
+    let mut mac = HmacSha256::new_from_slice(auth_key).expect("HMAC accepts any key length");
+    mac.update(nonce);
+    mac.update(ciphertext);
+    mac.verify_slice(tag).is_ok()
+}
+
+/// Trame complète : type || seq || ciphertext || HMAC(nonce || type || seq || ciphertext)
+/// Le numéro de séquence (u64 big-endian) est en clair mais couvert par le HMAC
+fn seal(msg_type: MessageType, seq: u64, ciphertext: &[u8], auth_key: &[u8], nonce: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(1 + 8 + ciphertext.len() + 32);
+    frame.push(msg_type as u8);
+    frame.extend_from_slice(&seq.to_be_bytes());
+    frame.extend_from_slice(ciphertext);
+    let tag = compute_tag(auth_key, nonce, &frame);
+    frame.extend_from_slice(&tag);
+    frame
+}
+
+/// Anti-rejeu : accepte `received` seulement s'il dépasse le dernier numéro accepté
+/// `expected` est le plus petit numéro encore acceptable
+fn check_and_advance_seq(expected: &mut u64, received: u64) -> bool {
+    if received < *expected {
+        return false;
     }
+    *expected = received + 1;
+    true
+}
 
-    fn process(&mut self, data: &[u8], mode: &str) -> Vec<u8> {
-        let start_pos = self.count;
-        let mut out = Vec::new();
-        let mut key_bytes = Vec::new();
+/// État cryptographique d'un sens de communication (envoi ou réception)
+struct Direction {
+    cipher: Box<dyn StreamCipher>,
+    auth_key: Vec<u8>,
+    nonce: Vec<u8>,
+    /// Messages passés dans ce sens depuis la dernière (re)négociation
+    messages: u64,
+    /// Envoi : prochain numéro de séquence ; réception : plus petit numéro acceptable
+    seq: u64,
+}
 
-        for &b in data {
-            let k = self.next_byte();
-            key_bytes.push(k);
-            out.push(b ^ k);
-        }
+impl Direction {
+    fn new(shared_secret: u64, nonce: Vec<u8>, legacy: bool) -> Self {
+        let (cipher, auth_key) = Self::keys(shared_secret, &nonce, legacy);
+        Direction { cipher, auth_key, nonce, messages: 0, seq: 0 }
+    }
 
-        println!("[{}]", mode);
-        if mode == "ENCRYPT" {
-             print!("Plain: ");
-             for b in data { print!("{:02x} ", b); }
-             if let Ok(s) = std::str::from_utf8(data) { print!("({:?})", s); }
-             println!();
+    fn keys(shared_secret: u64, nonce: &[u8], legacy: bool) -> (Box<dyn StreamCipher>, Vec<u8>) {
+        let (encryption_key, auth_key) = derive_keys(shared_secret);
+        let cipher: Box<dyn StreamCipher> = if legacy {
+            Box::new(LcgCipher::new(shared_secret))
         } else {
-             print!("Cipher: ");
-             for b in data { print!("{:02x} ", b); }
-             println!();
-        }
+            let key: [u8; 32] = encryption_key.try_into().expect("SHA-256 output is 32 bytes");
+            let nonce: [u8; 12] = nonce.try_into().expect("ChaCha20 nonce is 12 bytes");
+            Box::new(ChaCha20Cipher::new(&key, &nonce, 0))
+        };
+        (cipher, auth_key)
+    }
 
-        print!("Key: ");
-        for k in &key_bytes { print!("{:02x} ", k); }
-        println!(" (keystream position: {})", start_pos);
+    /// Nouveau secret : keystream et clé HMAC remplacés, compteurs remis à zéro
+    fn rekey(&mut self, shared_secret: u64, legacy: bool) {
+        (self.cipher, self.auth_key) = Self::keys(shared_secret, &self.nonce, legacy);
+        self.messages = 0;
+        self.seq = 0;
+    }
 
-        if mode == "ENCRYPT" {
-            print!("Cipher: ");
-            for b in &out { print!("{:02x} ", b); }
-            println!();
-        } else {
-            print!("Plain: ");
-            for b in &out { print!("{:02x} ", b); }
-            if let Ok(s) = std::str::from_utf8(&out) { print!(" -> {:?}", s); }
-            println!();
-        }
-        println!(); 
+    /// Scelle une trame sortante avec le prochain numéro de séquence
+    fn seal(&mut self, msg_type: MessageType, ciphertext: &[u8]) -> Vec<u8> {
+        let frame = seal(msg_type, self.seq, ciphertext, &self.auth_key, &self.nonce);
+        self.seq += 1;
+        frame
+    }
 
-        out
+    /// Trame = type || seq (8 octets) || données || tag (32 octets)
+    fn verify(&self, frame: &[u8]) -> bool {
+        let n = frame.len();
+        n >= 1 + 8 + 32 && verify_tag(&self.auth_key, &self.nonce, &frame[..n - 32], &frame[n - 32..])
     }
 }
 
@@ -135,82 +399,328 @@ impl LcgCipher {
 // 3. LOGIQUE RESEAU
 // ==========================================
 
-fn main() {
-    let args = Cli::parse();
+fn parse_hex_u64(s: &str) -> Result<u64, ChatError> {
+    let digits = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+    u64::from_str_radix(digits, 16)
+        .map_err(|e| ChatError::ParseError(format!("invalid 64-bit hex value '{}': {}", s, e)))
+}
 
-    match args.command {
-        Commands::Server { port } => start_server(port),
-        Commands::Client { host } => start_client(&host),
+/// Paramètres publics du Diffie-Hellman, choisis par le serveur
+#[derive(Clone, Copy)]
+struct DhParams {
+    p: u64,
+    g: u64,
+}
+
+impl DhParams {
+    /// Envoyés au client sous forme de deux u64 big-endian : P puis G
+    fn to_bytes(self) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&self.p.to_be_bytes());
+        bytes[8..].copy_from_slice(&self.g.to_be_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: [u8; 16]) -> Self {
+        DhParams {
+            p: u64::from_be_bytes(bytes[..8].try_into().unwrap()),
+            g: u64::from_be_bytes(bytes[8..].try_into().unwrap()),
+        }
+    }
+
+    /// Contrôle au démarrage du serveur : P impair et G < P
+    fn for_server(prime: u64, generator: u64) -> Result<Self, ChatError> {
+        if prime.is_multiple_of(2) {
+            return Err(ChatError::InvalidParameters(format!("--prime must be odd (got {:X})", prime)));
+        }
+        if generator >= prime {
+            return Err(ChatError::InvalidParameters(format!(
+                "--generator must be smaller than the prime ({} >= {:X})",
+                generator, prime
+            )));
+        }
+        Ok(DhParams { p: prime, g: generator })
+    }
+
+    /// Contrôle côté client des paramètres imposés par le serveur
+    fn check_received(self) -> Result<Self, ChatError> {
+        if !miller_rabin(self.p, MILLER_RABIN_ROUNDS) {
+            return Err(ChatError::InvalidParameters(format!("server sent a non-prime modulus p = {:X}", self.p)));
+        }
+        if self.g < 2 || self.g >= self.p {
+            return Err(ChatError::InvalidParameters(format!("server sent an invalid generator g = {}", self.g)));
+        }
+        Ok(self)
     }
 }
 
-fn handle_connection(mut stream: TcpStream) {
-    let peer_addr = stream.peer_addr().unwrap();
-    println!("[CLIENT] Connected from {}", peer_addr);
+/// Options sans effet en --tls (rustls gère clés, délais et affichage à sa façon)
+const TLS_CONFLICTS: &[&str] = &[
+    "legacy", "password", "latency", "log", "max_file_size", "rekey_interval", "timeout", "no_echo",
+    "prime", "generator", "group", "relay_log", "dual_stack", "unix",
+];
 
-    // --- DH HANDSHAKE ---
-    println!("\n[DH] Starting key exchange...");
-    println!("[DH] Using hardcoded DH parameters:");
-    println!("p = {:X} (64-bit prime - public)", P);
-    println!("g = {} (generator - public)\n", G);
+/// clap ne voit pas les conflits avec --tls quand il précède la sous-commande :
+/// on les revérifie sur les arguments de la sous-commande (globaux compris)
+fn tls_conflict(matches: &ArgMatches) -> Option<String> {
+    let (name, sub) = matches.subcommand()?;
+    if !sub.get_flag("tls") {
+        return None;
+    }
+    let mut command = Cli::command();
+    command.build();
+    let sub_command = command.find_subcommand(name)?;
+    sub_command
+        .get_arguments()
+        .filter(|arg| TLS_CONFLICTS.contains(&arg.get_id().as_str()))
+        .find(|arg| sub.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine))
+        .and_then(|arg| arg.get_long())
+        .map(|long| format!("--{}", long))
+}
 
-    println!("[DH] Generating our keypair...");
-    // CORRECTION : Utilisation de rng().random() pour Rand 0.9+
-    let private_key: u64 = rand::rng().random(); 
-    println!("private_key = {:X} (random 64-bit)", private_key);
+fn main() {
+    let matches = Cli::command().get_matches();
+    if let Some(flag) = tls_conflict(&matches) {
+        Cli::command()
+            .error(ErrorKind::ArgumentConflict, format!("the argument '{}' cannot be used with '--tls'", flag))
+            .exit();
+    }
+    let args = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let log = match &args.log {
+        Some(path) => match Logger::open(path, args.log_encrypt) {
+            Ok(logger) => Some(Arc::new(Mutex::new(logger))),
+            Err(e) => {
+                eprintln!("Error: Could not open log file {}. {}", path.display(), e);
+                process::exit(1);
+            }
+        },
+        None => None,
+    };
+    let options = SessionOptions {
+        legacy: args.legacy,
+        max_file_size: args.max_file_size,
+        rekey_interval: args.rekey_interval,
+        timeout: args.timeout,
+        password: args.password.map(Arc::from),
+        latency: args.latency,
+        log,
+        renderer: ChatRenderer { echo: !args.no_echo },
+    };
 
-    let public_key = mod_pow(G, private_key, P);
-    println!("public_key = g^private mod p");
-    println!("= {}^{:X} mod p", G, private_key);
-    println!("= {:X}\n", public_key);
+    match args.command {
+        // TLS : ni paramètres DH ni keystream, rustls s'occupe de tout
+        Commands::Server { port, max_clients, ipv6, .. } if args.tls => {
+            tls::start_server(resolve_bind_addr(port, ipv6), max_clients)
+        }
+        Commands::Client { host: Some(host), .. } if args.tls => tls::start_client(&host),
+        Commands::Server { port, max_clients, prime, generator, group, relay_log, ipv6, dual_stack, unix } => {
+            // Validation des paramètres DH (et du journal de relais) au démarrage
+            let setup = DhParams::for_server(prime.unwrap_or(P), generator).and_then(|params| {
+                let relay = if group { Some(Arc::new(Relay::new(relay_log.as_deref())?)) } else { None };
+                Ok((params, relay))
+            });
+            match setup {
+                Ok((params, relay)) => {
+                    // --dual-stack : un listener par famille, IPv6 restreint à IPv6 (V6ONLY)
+                    let addrs = if dual_stack {
+                        vec![resolve_bind_addr(port, false), resolve_bind_addr(port, true)]
+                    } else {
+                        vec![resolve_bind_addr(port, ipv6)]
+                    };
+                    let state = ServerState {
+                        max_clients,
+                        params,
+                        options,
+                        relay,
+                        active: Arc::new(AtomicUsize::new(0)),
+                        next_id: Arc::new(AtomicUsize::new(1)),
+                    };
+                    match unix {
+                        Some(path) => start_unix_server(&path, state),
+                        None => start_server(&addrs, dual_stack, state),
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::Client { unix: Some(path), .. } => start_unix_client(&path, options),
+        Commands::Client { host, .. } => start_client(&host.unwrap_or_default(), options),
+    }
+}
 
-    println!("[DH] Exchanging keys...");
-    println!("[NETWORK] Sending public key (8 bytes)...");
-    println!("-> Send our public: {:X}", public_key);
-    if let Err(e) = stream.write_all(&public_key.to_be_bytes()) {
-        eprintln!("Error sending key: {}", e);
-        return;
+/// Place occupée par un client côté serveur : libérée automatiquement (Drop)
+/// quand la connexion se termine
+struct ClientSlot {
+    id: usize,
+    active: Arc<AtomicUsize>,
+}
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        let remaining = self.active.fetch_sub(1, Ordering::SeqCst) - 1;
+        println!("[#{}] Client disconnected ({} active)", self.id, remaining);
     }
+}
 
-    let mut buffer = [0u8; 8];
-    if let Err(e) = stream.read_exact(&mut buffer) {
-        eprintln!("Error receiving key: {}", e);
-        return;
+/// `slot` est None côté client : une déconnexion du pair termine alors le programme
+/// `relay` (serveur --group) : les messages viennent du hub au lieu de stdin
+fn handle_connection(
+    mut stream: Box<dyn Transport>,
+    params: DhParams,
+    options: SessionOptions,
+    slot: Option<ClientSlot>,
+    relay: Option<Arc<Relay>>,
+) {
+    let SessionOptions { legacy, max_file_size, rekey_interval, timeout, latency, .. } = options;
+    let peer_addr = stream.peer_label();
+    match &slot {
+        Some(slot) => println!("[#{}] [CLIENT] Connected from {}", slot.id, peer_addr),
+        None => println!("[CLIENT] Connected from {}", peer_addr),
     }
-    let their_public_key = u64::from_be_bytes(buffer);
-    println!("[NETWORK] Received public key (8 bytes) ✓");
-    println!("<- Receive their public: {:X}\n", their_public_key);
 
-    println!("[DH] Computing shared secret...");
-    println!("Formula: secret = (their_public)^(our_private) mod p");
-    let shared_secret = mod_pow(their_public_key, private_key, P);
-    println!("secret = ({:X})^({:X}) mod p", their_public_key, private_key);
-    println!("= {:X}\n", shared_secret);
+    let (mut sending, mut receiving) = match establish_session(stream.as_mut(), params, &options, slot.is_some()) {
+        Ok(directions) => directions,
+        Err(e @ ChatError::PeerRejected { .. }) => {
+            println!("[AUTH] {}", e);
+            let _ = stream.shutdown();
+            return;
+        }
+        Err(e) => {
+            eprintln!("Error during handshake: {}", e);
+            return;
+        }
+    };
 
-    let mut cipher = LcgCipher::new(shared_secret);
-    println!("✓ Secure channel established!\n");
+    // --- LATENCE (--latency) : le client mesure, le serveur renvoie l'écho ---
+    if latency {
+        let result = if slot.is_some() {
+            echo_latency(stream.as_mut(), &mut sending, &mut receiving)
+        } else {
+            measure_latency(stream.as_mut(), &mut sending, &mut receiving).map(|stats| stats.print())
+        };
+        if let Err(e) = result {
+            eprintln!("[LATENCY] Measurement aborted: {}", e);
+            return;
+        }
+    }
 
     // --- CHAT LOOP ---
-    let mut stream_reader = stream.try_clone().expect("Clone failed");
-    let shared_secret_copy = shared_secret;
-    
+    let mut stream_reader = stream.try_clone_transport().expect("Clone failed");
+    // Les deux threads écrivent (messages, acquittements de rekey) : écriture sérialisée
+    let writer = Arc::new(Mutex::new(stream));
+    let ack_writer = Arc::clone(&writer);
+    let (ack_tx, ack_rx) = mpsc::channel::<Vec<u8>>();
+
+    // Mode groupe : inscription au hub, la boucle d'envoi draine notre canal
+    let conn_id = slot.as_ref().map_or(0, |slot| slot.id);
+    let outbound = relay.as_ref().map(|relay| relay.register(conn_id));
+    let recv_relay = relay.clone();
+    let recv_log = options.log.clone();
+    let renderer = options.renderer;
+
+    // Heartbeat : alimenté par le thread de réception, il envoie /ping et ferme si pas de /pong
+    let (heartbeat_tx, heartbeat_rx) = mpsc::channel::<Heartbeat>();
+    let heartbeat_writer = Arc::clone(&writer);
+    thread::spawn(move || run_heartbeat(&heartbeat_writer, &heartbeat_rx, timeout));
+
     // Thread de réception
     thread::spawn(move || {
-        let mut decryptor = LcgCipher::new(shared_secret_copy);
-        let mut buffer = [0u8; 1024];
+        // Le slot vit avec le thread de réception : libéré à la déconnexion
+        let slot = slot;
+        // Désinscription du hub en quittant : le canal se ferme et la boucle d'envoi s'arrête
+        let _registration = recv_relay.as_ref().map(|relay| relay.guard(conn_id));
+        let mut incoming_file: Option<IncomingFile> = None;
         loop {
-            match stream_reader.read(&mut buffer) {
-                Ok(n) if n > 0 => {
+            let frame = recv_framed(&mut stream_reader);
+            if frame.is_ok() {
+                let _ = heartbeat_tx.send(Heartbeat::Activity);
+            }
+            match frame {
+                // Aucune donnée depuis --timeout secondes : le heartbeat prend le relais
+                Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+                    let _ = heartbeat_tx.send(Heartbeat::Idle);
+                }
+                // Trames de contrôle sans contenu (ni chiffrées ni signées)
+                Ok(data) if data == [MessageType::Ping as u8] => {
+                    let _ = send_framed(ack_writer.lock().unwrap().as_mut(), &[MessageType::Pong as u8]);
+                }
+                Ok(data) if data == [MessageType::Pong as u8] => {}
+                // Acquittement de rekey : signé avec la clé de NOTRE sens d'envoi,
+                // vérifié par la boucle d'envoi qui l'attend
+                Ok(data) if data.first() == Some(&(MessageType::RekeyAck as u8)) => {
+                    let _ = ack_tx.send(data);
+                }
+                Ok(data) => {
+                    let n = data.len();
                     println!("\n[NETWORK] Received encrypted message ({} bytes)", n);
                     println!("[<-] Received {} bytes\n", n);
                     
-                    let encrypted_data = &buffer[0..n];
-                    decryptor.process(encrypted_data, "DECRYPT");
+                    // Les 32 derniers octets sont le tag HMAC : on vérifie AVANT de déchiffrer
+                    let seq = data.get(1..9).map_or(0, |b| u64::from_be_bytes(b.try_into().unwrap()));
+                    if !receiving.verify(&data) {
+                        println!("[WARNING] Message authentication failed — discarding");
+                    } else if !check_and_advance_seq(&mut receiving.seq, seq) {
+                        println!("[SECURITY] Replayed or out-of-order message rejected (seq={})", seq);
+                    } else {
+                        println!("[MAC] Tag verified ✓ (seq={})", seq);
+                        let ciphertext = &data[9..n - 32];
+                        match MessageType::from_byte(data[0]) {
+                            Some(MessageType::Text) => {
+                                let position = receiving.cipher.position();
+                                let plain = receiving.cipher.process(ciphertext, "DECRYPT");
+                                if let Some(log) = &recv_log {
+                                    record(log, LogDirection::Recv, &plain, ciphertext, position);
+                                }
+                                renderer.print_received(&String::from_utf8_lossy(&plain));
+                                receiving.messages += 1;
+                                if let Some(relay) = &recv_relay {
+                                    relay.broadcast(conn_id, &plain);
+                                }
+                            }
+                            Some(MessageType::File) => {
+                                let chunk = receiving.cipher.apply(ciphertext);
+                                receiving.messages += 1;
+                                receive_file_chunk(&chunk, &mut incoming_file, max_file_size);
+                            }
+                            Some(MessageType::Rekey) => {
+                                if let Err(e) = answer_rekey(&ack_writer, ciphertext, &mut receiving, params, legacy) {
+                                    eprintln!("\n[REKEY] Failed to acknowledge: {}", e);
+                                }
+                            }
+                            Some(
+                                MessageType::RekeyAck
+                                | MessageType::Ping
+                                | MessageType::Pong
+                                | MessageType::LatencyPing
+                                | MessageType::LatencyEcho,
+                            )
+                            | None => {
+                                println!("[WARNING] Unexpected message type 0x{:02x} — discarding", data[0])
+                            }
+                        }
+                    }
                     
-                    print!("\n[CHAT] Type message:\n> ");
-                    io::stdout().flush().unwrap();
+                    if recv_relay.is_none() {
+                        print!("\n[CHAT] Type message:\n> ");
+                        io::stdout().flush().unwrap();
+                    }
                 },
-                Ok(_) => { println!("Peer disconnected."); process::exit(0); }
+                // Trame trop grande : on coupe la connexion
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                    eprintln!("\n[ERROR] {} — disconnecting", e);
+                    let _ = stream_reader.shutdown();
+                    if slot.is_some() { return; }
+                    process::exit(1);
+                }
+                // Côté serveur on ferme seulement cette connexion, côté client on quitte
+                Err(_) if slot.is_some() => {
+                    let _ = stream_reader.shutdown();
+                    return;
+                }
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => { println!("Peer disconnected."); process::exit(0); }
                 Err(_) => { process::exit(0); }
             }
         }
@@ -218,55 +728,805 @@ fn handle_connection(mut stream: TcpStream) {
 
     // Boucle d'envoi
     loop {
-        print!("[CHAT] Type message:\n> ");
-        io::stdout().flush().unwrap();
+        // Rekey de notre sens d'envoi toutes les N messages
+        if rekey_interval > 0 && sending.messages >= rekey_interval
+            && let Err(e) = initiate_rekey(&writer, &ack_rx, &mut sending, params, legacy)
+        {
+            eprintln!("[REKEY] {} — closing connection", e);
+            let _ = writer.lock().unwrap().shutdown();
+            break;
+        }
 
         let mut input = String::new();
-        if io::stdin().read_line(&mut input).is_err() { break; }
+        if let Some(outbound) = &outbound {
+            // Mode groupe : message relayé d'un autre client (fin quand on quitte le hub)
+            match outbound.recv() {
+                Ok(payload) => input = String::from_utf8_lossy(&payload).into_owned(),
+                Err(_) => break,
+            }
+        } else {
+            print!("[CHAT] Type message:\n> ");
+            io::stdout().flush().unwrap();
+
+            // Fin de stdin (Ctrl+D) ou erreur : on arrête d'envoyer
+            match io::stdin().read_line(&mut input) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+        }
         
         let trimmed = input.trim();
         if trimmed.is_empty() { continue; }
 
+        // Commande /send <fichier>
+        if let Some(path) = trimmed.strip_prefix("/send ").filter(|_| outbound.is_none()) {
+            match send_file(&writer, path.trim(), &mut sending, max_file_size) {
+                Ok(()) => sending.messages += 1,
+                // Problème local (fichier absent, trop gros) : la session continue
+                Err(e @ (ChatError::FileError { .. } | ChatError::FileTooLarge { .. })) => {
+                    eprintln!("[FILE] Send failed: {}", e);
+                }
+                Err(e) => {
+                    eprintln!("[FILE] Send failed: {}", e);
+                    break;
+                }
+            }
+            continue;
+        }
+
         let bytes = trimmed.as_bytes();
-        let encrypted = cipher.process(bytes, "ENCRYPT");
+        let position = sending.cipher.position();
+        let encrypted = sending.cipher.process(bytes, "ENCRYPT");
+        if let Some(log) = &options.log {
+            record(log, LogDirection::Sent, bytes, &encrypted, position);
+        }
+
+        // Type en tête, tag HMAC ajouté après le ciphertext
+        let encrypted = sending.seal(MessageType::Text, &encrypted);
+        print!("MAC: ");
+        for b in &encrypted[encrypted.len() - 32..] { print!("{:02x}", b); }
+        println!();
 
         println!("[NETWORK] Sending encrypted message ({} bytes)...", encrypted.len());
-        match stream.write_all(&encrypted) {
-            Ok(_) => println!("[->] Sent {} bytes", encrypted.len()),
+        match send_framed(writer.lock().unwrap().as_mut(), &encrypted) {
+            Ok(_) => {
+                println!("[->] Sent {} bytes", encrypted.len());
+                sending.messages += 1;
+                options.renderer.print_sent(trimmed);
+            }
             Err(e) => { eprintln!("Send error: {}", e); break; }
         }
     }
 }
 
-fn start_server(port: u16) {
+/// Une erreur d'écriture du journal ne coupe pas la session
+fn record(log: &Mutex<Logger>, direction: LogDirection, plain: &[u8], ciphertext: &[u8], position: usize) {
+    if let Err(e) = log.lock().unwrap().record(direction, plain, ciphertext, position) {
+        eprintln!("[LOG] Write failed: {}", e);
+    }
+}
+
+/// Échange DH, confirmation du mot de passe (--password) puis (hors --legacy)
+/// des nonces : renvoie les états (envoi, réception)
+fn establish_session(
+    stream: &mut dyn Transport,
+    params: DhParams,
+    options: &SessionOptions,
+    is_server: bool,
+) -> Result<(Direction, Direction), ChatError> {
+    let DhParams { p, g } = params;
+
+    // Lecture bloquante limitée : au-delà, le thread heartbeat sonde le pair
+    stream.set_read_timeout(Some(Duration::from_secs(options.timeout)))?;
+
+    // --- DH HANDSHAKE ---
+    println!("\n[DH] Starting key exchange...");
+    println!("[DH] Using negotiated DH parameters:");
+    println!("p = {:X} (64-bit prime - public)", p);
+    println!("g = {} (generator - public)\n", g);
+
+    println!("[DH] Generating our keypair...");
+    // CORRECTION : Utilisation de rng().random() pour Rand 0.9+
+    let private_key: u64 = rand::rng().random(); 
+    println!("private_key = {:X} (random 64-bit)", private_key);
+
+    let public_key = mod_pow(g, private_key, p);
+    println!("public_key = g^private mod p");
+    println!("= {}^{:X} mod p", g, private_key);
+    println!("= {:X}\n", public_key);
+
+    println!("[DH] Exchanging keys...");
+    println!("[NETWORK] Sending public key (8 bytes)...");
+    println!("-> Send our public: {:X}", public_key);
+    stream.write_all(&public_key.to_be_bytes())?;
+
+    let mut buffer = [0u8; 8];
+    stream.read_exact(&mut buffer)?;
+    let their_public_key = u64::from_be_bytes(buffer);
+    println!("[NETWORK] Received public key (8 bytes) ✓");
+    println!("<- Receive their public: {:X}\n", their_public_key);
+
+    println!("[DH] Computing shared secret...");
+    println!("Formula: secret = (their_public)^(our_private) mod p");
+    let shared_secret = mod_pow(their_public_key, private_key, p);
+    println!("secret = ({:X})^({:X}) mod p", their_public_key, private_key);
+    println!("= {:X}\n", shared_secret);
+
+    if let Some(password) = &options.password {
+        confirm_password(stream, shared_secret, password, is_server)?;
+    }
+
+    // --- KEYSTREAM SETUP ---
+    println!("[MAC] Authentication key = SHA-256(secret || \"auth\")\n");
+
+    // Chaque sens a son propre keystream ChaCha20 (même clé, nonce différent)
+    // Le mode --legacy n'échange pas de nonce : le HMAC porte alors sur le seul ciphertext
+    let mut our_nonce = [0u8; 12].to_vec();
+    let mut their_nonce = [0u8; 12].to_vec();
+    if options.legacy {
+        our_nonce.clear();
+        their_nonce.clear();
+    } else {
+        rand::rng().fill(&mut our_nonce[..]);
+
+        println!("[NETWORK] Sending nonce (12 bytes)...");
+        stream.write_all(&our_nonce)?;
+        stream.read_exact(&mut their_nonce)?;
+        println!("[NETWORK] Received nonce (12 bytes) ✓\n");
+
+        let (encryption_key, _) = derive_keys(shared_secret);
+        let key: [u8; 32] = encryption_key.try_into().expect("SHA-256 output is 32 bytes");
+        ChaCha20Cipher::announce(&key, our_nonce[..].try_into().unwrap());
+    }
+    let sending = Direction::new(shared_secret, our_nonce, options.legacy);
+    let receiving = Direction::new(shared_secret, their_nonce, options.legacy);
+    println!("✓ Secure channel established!\n");
+    Ok((sending, receiving))
+}
+
+/// Adresse d'écoute du serveur : toutes les interfaces IPv4, ou IPv6 avec --ipv6
+fn resolve_bind_addr(port: u16, ipv6: bool) -> SocketAddr {
+    if ipv6 {
+        SocketAddr::from((Ipv6Addr::UNSPECIFIED, port))
+    } else {
+        SocketAddr::from((Ipv4Addr::UNSPECIFIED, port))
+    }
+}
+
+/// Comme TcpListener::bind, avec en plus le choix de V6ONLY : sans lui, [::] capte
+/// aussi l'IPv4 sous Linux et le bind 0.0.0.0 de --dual-stack échouerait
+fn bind_listener(addr: SocketAddr, v6_only: bool) -> io::Result<TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+    socket.set_reuse_address(true)?;
+    if v6_only && addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
+    socket.bind(&addr.into())?;
+    socket.listen(128)?;
+    Ok(socket.into())
+}
+
+/// État partagé par les boucles d'acceptation (une par listener avec --dual-stack)
+#[derive(Clone)]
+struct ServerState {
+    max_clients: usize,
+    params: DhParams,
+    options: SessionOptions,
+    relay: Option<Arc<Relay>>,
+    active: Arc<AtomicUsize>,
+    next_id: Arc<AtomicUsize>,
+}
+
+fn start_server(addrs: &[SocketAddr], v6_only: bool, state: ServerState) {
     // CORRECTION : Gestion propre de l'erreur de bind (Exit code 1)
-    let listener = match TcpListener::bind(format!("0.0.0.0:{}", port)) {
+    let mut listeners = Vec::new();
+    for &addr in addrs {
+        match bind_listener(addr, v6_only) {
+            Ok(l) => listeners.push(l),
+            Err(e) => {
+                eprintln!("Error: Could not bind to {}. {}", addr, e);
+                process::exit(1);
+            }
+        }
+        println!("[SERVER] Listening on {}", addr);
+    }
+
+    println!("[SERVER] DH parameters: p = {:X}, g = {}", state.params.p, state.params.g);
+    if state.relay.is_some() {
+        println!("[SERVER] Group mode: relaying messages between clients");
+    }
+    println!("[SERVER] Waiting for clients (max {})...\n", state.max_clients);
+
+    // Une boucle d'acceptation par listener, la dernière sur le thread principal
+    let last = listeners.pop().expect("at least one listen address");
+    for listener in listeners {
+        let state = state.clone();
+        thread::spawn(move || accept_clients(tcp_incoming(&listener), state));
+    }
+    accept_clients(tcp_incoming(&last), state);
+}
+
+fn tcp_incoming(listener: &TcpListener) -> impl Iterator<Item = io::Result<Box<dyn Transport>>> + '_ {
+    listener.incoming().map(|s| s.map(|s| Box::new(TcpTransport(s)) as Box<dyn Transport>))
+}
+
+/// Serveur sur socket Unix : même protocole que TCP, sans exposition réseau
+fn start_unix_server(path: &Path, state: ServerState) {
+    // Un socket resté d'une exécution précédente empêcherait le bind
+    if let Ok(meta) = fs::symlink_metadata(path) {
+        if !meta.file_type().is_socket() {
+            eprintln!("Error: {} exists and is not a socket", path.display());
+            process::exit(1);
+        }
+        if let Err(e) = fs::remove_file(path) {
+            eprintln!("Error: Could not remove stale socket {}. {}", path.display(), e);
+            process::exit(1);
+        }
+    }
+
+    let listener = match UnixListener::bind(path) {
         Ok(l) => l,
         Err(e) => {
-            eprintln!("Error: Could not bind to port {}. {}", port, e);
+            eprintln!("Error: Could not bind to {}. {}", path.display(), e);
             process::exit(1);
         }
     };
 
-    println!("[SERVER] Listening on 0.0.0.0:{}", port);
-    println!("[SERVER] Waiting for client...\n");
+    println!("[SERVER] Listening on unix:{}", path.display());
+    println!("[SERVER] DH parameters: p = {:X}, g = {}", state.params.p, state.params.g);
+    if state.relay.is_some() {
+        println!("[SERVER] Group mode: relaying messages between clients");
+    }
+    println!("[SERVER] Waiting for clients (max {})...\n", state.max_clients);
+
+    let incoming = listener.incoming().map(|s| s.map(|s| Box::new(UnixTransport(s)) as Box<dyn Transport>));
+    accept_clients(incoming, state);
+}
+
+fn accept_clients(incoming: impl Iterator<Item = io::Result<Box<dyn Transport>>>, state: ServerState) {
+    let ServerState { max_clients, params, active, next_id, .. } = &state;
+    let (max_clients, params) = (*max_clients, *params);
 
-    if let Ok((stream, _)) = listener.accept() {
-        handle_connection(stream);
+    for incoming in incoming {
+        let mut stream = match incoming {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("[SERVER] Accept error: {}", e);
+                continue;
+            }
+        };
+
+        // Serveur plein : on accepte puis on ferme aussitôt avec un message
+        if active.load(Ordering::SeqCst) >= max_clients {
+            println!("[SERVER] Rejecting {}: server full ({} clients)", stream.peer_label(), max_clients);
+            let _ = stream.write_all(&[STATUS_SERVER_FULL]);
+            let _ = stream.write_all(b"Server full");
+            continue;
+        }
+
+        // Accueil puis négociation : (P, G) avant l'échange des clés publiques
+        if stream.write_all(&[STATUS_ACCEPTED]).is_err() || stream.write_all(&params.to_bytes()).is_err() {
+            continue;
+        }
+        active.fetch_add(1, Ordering::SeqCst);
+        let slot = ClientSlot { id: next_id.fetch_add(1, Ordering::SeqCst), active: Arc::clone(active) };
+
+        let relay = state.relay.clone();
+        let options = state.options.clone();
+        thread::spawn(move || handle_connection(stream, params, options, Some(slot), relay));
     }
 }
 
-fn start_client(host: &str) {
+fn start_client(host: &str, options: SessionOptions) {
     println!("[CLIENT] Connecting to {}...", host);
     // CORRECTION : Gestion propre de l'erreur de connexion (Exit code 1)
-    match TcpStream::connect(host) {
-        Ok(stream) => {
-            println!("[CLIENT] Connected!");
-            handle_connection(stream);
-        },
+    // "hôte:port" ou "[::1]:port" : ToSocketAddrs gère les deux formes (et les noms DNS),
+    // chaque adresse résolue est essayée dans l'ordre
+    let result = host
+        .to_socket_addrs()
+        .and_then(|addrs| TcpStream::connect(&addrs.collect::<Vec<SocketAddr>>()[..]))
+        .map_err(ChatError::from)
+        .and_then(|stream| connect(Box::new(TcpTransport(stream))));
+    match result {
+        Ok((stream, params)) => handle_connection(stream, params, options, None, None),
         Err(e) => {
             eprintln!("Error: Failed to connect to {}. {}", host, e);
             process::exit(1);
-        },
+        }
+    }
+}
+
+/// Le fichier du socket appartient au serveur : le client ne le supprime jamais
+fn start_unix_client(path: &Path, options: SessionOptions) {
+    println!("[CLIENT] Connecting to unix:{}...", path.display());
+    let result = UnixStream::connect(path)
+        .map_err(ChatError::from)
+        .and_then(|stream| connect(Box::new(UnixTransport(stream))));
+    match result {
+        Ok((stream, params)) => handle_connection(stream, params, options, None, None),
+        Err(e) => {
+            eprintln!("Error: Failed to connect to {}. {}", path.display(), e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Octet d'accueil puis réception et vérification de (P, G)
+fn connect(mut stream: Box<dyn Transport>) -> Result<(Box<dyn Transport>, DhParams), ChatError> {
+    // Octet d'accueil : le serveur peut refuser s'il est plein
+    let mut status = [0u8; 1];
+    stream.read_exact(&mut status)?;
+    if status[0] != STATUS_ACCEPTED {
+        let mut reason = String::new();
+        let _ = stream.read_to_string(&mut reason);
+        return Err(ChatError::ConnectionRefused(reason));
     }
-}
\ No newline at end of file
+    println!("[CLIENT] Connected!");
+
+    // Négociation : le serveur impose (P, G), on vérifie que P est premier
+    let mut raw = [0u8; 16];
+    stream.read_exact(&mut raw)?;
+    let params = DhParams::from_bytes(raw);
+    println!("[DH] Received parameters from server: p = {:X}, g = {}", params.p, params.g);
+    let params = params.check_received()?;
+    println!("[DH] p passed Miller-Rabin ({} rounds) ✓", MILLER_RABIN_ROUNDS);
+    Ok((stream, params))
+}
+
+// ==========================================
+// 4. TRANSFERT DE FICHIERS
+// ==========================================
+
+// Chaque morceau (chiffré) : len(nom) u16 || nom || taille totale u64 || offset u64 || données
+
+/// Fichier en cours de réception
+struct IncomingFile {
+    name: String,
+    file: File,
+    total: u64,
+    received: u64,
+}
+
+/// Envoie un fichier par morceaux de FILE_CHUNK_SIZE avec affichage de la progression
+fn send_file(writer: &Mutex<Box<dyn Transport>>, path: &str, sending: &mut Direction, max_file_size: u64) -> Result<(), ChatError> {
+    let content = fs::read(path).map_err(|source| ChatError::FileError { path: path.to_string(), source })?;
+    if content.len() as u64 > max_file_size {
+        return Err(ChatError::FileTooLarge { size: content.len() as u64, max: max_file_size });
+    }
+    let name = Path::new(path).file_name().and_then(|n| n.to_str()).ok_or_else(|| ChatError::FileError {
+        path: path.to_string(),
+        source: io::Error::new(io::ErrorKind::InvalidInput, "invalid file name"),
+    })?;
+
+    println!("[FILE] Sending {} ({} bytes)", name, content.len());
+    let total = content.len() as u64;
+    let mut offset = 0usize;
+    loop {
+        let end = (offset + FILE_CHUNK_SIZE).min(content.len());
+
+        let mut plain = Vec::with_capacity(2 + name.len() + 16 + end - offset);
+        plain.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        plain.extend_from_slice(name.as_bytes());
+        plain.extend_from_slice(&total.to_be_bytes());
+        plain.extend_from_slice(&(offset as u64).to_be_bytes());
+        plain.extend_from_slice(&content[offset..end]);
+
+        let encrypted = sending.cipher.apply(&plain);
+        send_framed(writer.lock().unwrap().as_mut(), &sending.seal(MessageType::File, &encrypted))?;
+
+        let percent = (end as u64 * 100).checked_div(total).unwrap_or(100);
+        print!("\rSending: {}%", percent);
+        io::stdout().flush().unwrap();
+
+        offset = end;
+        if offset >= content.len() { break; }
+    }
+    println!("\n[FILE] Sent {} ✓", name);
+    Ok(())
+}
+
+/// Traite un morceau déchiffré ; écrit dans received_<nom> du répertoire courant
+fn receive_file_chunk(chunk: &[u8], incoming: &mut Option<IncomingFile>, max_file_size: u64) {
+    let Some((name, total, offset, data)) = parse_file_chunk(chunk) else {
+        println!("[WARNING] Malformed file chunk — discarding");
+        return;
+    };
+
+    if offset == 0 {
+        // Seul le nom de base est utilisé : pas d'écriture hors du répertoire courant
+        let Some(base) = Path::new(&name).file_name().and_then(|n| n.to_str()) else {
+            println!("[WARNING] Invalid file name {:?} — discarding", name);
+            *incoming = None;
+            return;
+        };
+        if total > max_file_size {
+            println!("[WARNING] File {} too large ({} bytes, max {}) — discarding", base, total, max_file_size);
+            *incoming = None;
+            return;
+        }
+        let out_name = format!("received_{}", base);
+        match File::create(&out_name) {
+            Ok(file) => {
+                println!("[FILE] Receiving {} ({} bytes)", base, total);
+                *incoming = Some(IncomingFile { name: out_name, file, total, received: 0 });
+            }
+            Err(e) => {
+                println!("[WARNING] Cannot create {}: {}", out_name, e);
+                *incoming = None;
+                return;
+            }
+        }
+    }
+
+    // Morceau d'un fichier refusé (ou désordonné) : ignoré
+    let Some(current) = incoming.as_mut() else { return; };
+    if offset != current.received || current.received + data.len() as u64 > current.total {
+        println!("[WARNING] Unexpected chunk for {} — transfer aborted", current.name);
+        *incoming = None;
+        return;
+    }
+    if let Err(e) = current.file.write_all(data) {
+        println!("[WARNING] Write error on {}: {}", current.name, e);
+        *incoming = None;
+        return;
+    }
+    current.received += data.len() as u64;
+
+    if current.received == current.total {
+        println!("[FILE] Saved {} ({} bytes) ✓", current.name, current.total);
+        *incoming = None;
+    }
+}
+
+fn parse_file_chunk(chunk: &[u8]) -> Option<(String, u64, u64, &[u8])> {
+    let name_len = u16::from_be_bytes(chunk.get(..2)?.try_into().ok()?) as usize;
+    let name = String::from_utf8(chunk.get(2..2 + name_len)?.to_vec()).ok()?;
+    let rest = chunk.get(2 + name_len..)?;
+    let total = u64::from_be_bytes(rest.get(..8)?.try_into().ok()?);
+    let offset = u64::from_be_bytes(rest.get(8..16)?.try_into().ok()?);
+    Some((name, total, offset, &rest[16..]))
+}
+
+// ==========================================
+// 5. RENOUVELLEMENT DES CLÉS (FORWARD SECRECY)
+// ==========================================
+
+// Chaque sens est renouvelé indépendamment par son émetteur :
+// - l'émetteur envoie Rekey (0x03) avec une clé publique éphémère, puis se met en pause
+// - le pair répond RekeyAck (0x04) avec la sienne ; les deux trames sont signées
+//   avec l'ancienne clé HMAC du sens renouvelé
+// Les anciennes clés privées éphémères sont oubliées : un secret compromis plus tard
+// ne permet pas de déchiffrer les messages passés
+
+/// Côté émetteur : bloque l'envoi jusqu'à l'acquittement (ou l'expiration du délai)
+fn initiate_rekey(
+    writer: &Mutex<Box<dyn Transport>>,
+    ack_rx: &Receiver<Vec<u8>>,
+    sending: &mut Direction,
+    params: DhParams,
+    legacy: bool,
+) -> Result<(), ChatError> {
+    // Acquittements périmés éventuels
+    while ack_rx.try_recv().is_ok() {}
+
+    let private_key: u64 = rand::rng().random();
+    let public_key = mod_pow(params.g, private_key, params.p);
+    println!("\n[REKEY] {} messages sent, renegotiating keys...", sending.messages);
+    println!("-> Send our ephemeral public: {:X}", public_key);
+    send_framed(writer.lock().unwrap().as_mut(), &sending.seal(MessageType::Rekey, &public_key.to_be_bytes()))?;
+
+    let ack = ack_rx.recv_timeout(REKEY_ACK_TIMEOUT).map_err(|_| ChatError::RekeyTimeout)?;
+    if ack.len() != 1 + 8 + 8 + 32 || !sending.verify(&ack) {
+        return Err(ChatError::AuthenticationFailed);
+    }
+    let their_public_key = u64::from_be_bytes(ack[9..17].try_into().unwrap());
+    println!("<- Receive their ephemeral public: {:X}", their_public_key);
+
+    sending.rekey(mod_pow(their_public_key, private_key, params.p), legacy);
+    println!("[REKEY] Outgoing keys renewed ✓\n");
+    Ok(())
+}
+
+/// Côté récepteur : répond au Rekey puis bascule le sens de réception sur le nouveau secret
+fn answer_rekey(
+    writer: &Mutex<Box<dyn Transport>>,
+    payload: &[u8],
+    receiving: &mut Direction,
+    params: DhParams,
+    legacy: bool,
+) -> Result<(), ChatError> {
+    let their_public_key: [u8; 8] = payload
+        .try_into()
+        .map_err(|_| ChatError::ParseError("malformed rekey request".to_string()))?;
+    let their_public_key = u64::from_be_bytes(their_public_key);
+
+    let private_key: u64 = rand::rng().random();
+    let public_key = mod_pow(params.g, private_key, params.p);
+    println!("[REKEY] Peer renegotiating keys, ephemeral public: {:X}", their_public_key);
+    // L'acquittement n'est accepté que pendant l'attente d'un rekey précis : pas de séquence
+    let ack = seal(MessageType::RekeyAck, 0, &public_key.to_be_bytes(), &receiving.auth_key, &receiving.nonce);
+    send_framed(writer.lock().unwrap().as_mut(), &ack)?;
+
+    receiving.rekey(mod_pow(their_public_key, private_key, params.p), legacy);
+    println!("[REKEY] Incoming keys renewed ✓");
+    Ok(())
+}
+
+// ==========================================
+// 6. MODE GROUPE (RELAIS)
+// ==========================================
+
+// Modèle de confiance : chaque client a négocié un secret DH distinct avec le serveur,
+// il n'existe donc pas de clé commune au groupe. Le serveur DÉCHIFFRE chaque message
+// reçu puis le RECHIFFRE avec la clé de session de chaque destinataire.
+// Le serveur voit tout en clair : c'est un relais de confiance, pas un chiffrement
+// de bout en bout. Les clients doivent faire confiance à l'opérateur du serveur.
+
+/// Canal de sortie de chaque connexion, indexé par identifiant de connexion
+type Hub = Arc<Mutex<Vec<(usize, SyncSender<Vec<u8>>)>>>;
+
+// Messages en attente par destinataire avant d'être ignorés (client trop lent)
+const RELAY_QUEUE_SIZE: usize = 64;
+
+struct Relay {
+    hub: Hub,
+    log: Option<Mutex<File>>,
+}
+
+impl Relay {
+    fn new(log_path: Option<&Path>) -> Result<Self, ChatError> {
+        let log = match log_path {
+            Some(path) => {
+                let file = OpenOptions::new().create(true).append(true).open(path).map_err(|source| {
+                    ChatError::FileError { path: path.display().to_string(), source }
+                })?;
+                Some(Mutex::new(file))
+            }
+            None => None,
+        };
+        Ok(Relay { hub: Arc::new(Mutex::new(Vec::new())), log })
+    }
+
+    /// Inscrit une connexion et renvoie la file de messages à lui transmettre
+    fn register(&self, id: usize) -> Receiver<Vec<u8>> {
+        let (tx, rx) = mpsc::sync_channel(RELAY_QUEUE_SIZE);
+        self.hub.lock().unwrap().push((id, tx));
+        rx
+    }
+
+    /// Désinscription automatique (Drop) de la connexion `id`
+    fn guard(&self, id: usize) -> RelayRegistration {
+        RelayRegistration { hub: Arc::clone(&self.hub), id }
+    }
+
+    /// Transmet le clair reçu de `from` à tous les autres clients
+    fn broadcast(&self, from: usize, plain: &[u8]) {
+        let mut message = format!("[#{}] ", from).into_bytes();
+        message.extend_from_slice(plain);
+
+        if let Some(log) = &self.log {
+            let mut log = log.lock().unwrap();
+            let _ = writeln!(log, "{}", String::from_utf8_lossy(&message));
+        }
+
+        let hub = self.hub.lock().unwrap();
+        let mut delivered = 0;
+        for (id, tx) in hub.iter().filter(|(id, _)| *id != from) {
+            match tx.try_send(message.clone()) {
+                Ok(()) => delivered += 1,
+                Err(TrySendError::Full(_)) => println!("[RELAY] #{} is not keeping up — message dropped", id),
+                Err(TrySendError::Disconnected(_)) => {}
+            }
+        }
+        println!("[RELAY] #{} -> {} client(s)", from, delivered);
+    }
+}
+
+struct RelayRegistration {
+    hub: Hub,
+    id: usize,
+}
+
+impl Drop for RelayRegistration {
+    fn drop(&mut self) {
+        self.hub.lock().unwrap().retain(|(id, _)| *id != self.id);
+    }
+}
+
+// ==========================================
+// 7. HEARTBEAT (TIMEOUT / KEEPALIVE)
+// ==========================================
+
+/// Événements envoyés par le thread de réception au thread heartbeat
+enum Heartbeat {
+    /// Une trame est arrivée
+    Activity,
+    /// La lecture a expiré (--timeout)
+    Idle,
+}
+
+/// Sur Idle : envoie /ping puis attend une activité pendant PONG_TIMEOUT ;
+/// sans réponse la connexion est fermée (le thread de réception voit alors l'erreur)
+fn run_heartbeat(writer: &Mutex<Box<dyn Transport>>, events: &Receiver<Heartbeat>, timeout: u64) {
+    while let Ok(event) = events.recv() {
+        if let Heartbeat::Activity = event {
+            continue;
+        }
+
+        println!("\n[TIMEOUT] No data for {} seconds, sending /ping", timeout);
+        if send_framed(writer.lock().unwrap().as_mut(), &[MessageType::Ping as u8]).is_err() {
+            return;
+        }
+
+        let deadline = Instant::now() + PONG_TIMEOUT;
+        let answered = loop {
+            match events.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(Heartbeat::Activity) => break true,
+                Ok(Heartbeat::Idle) => continue,
+                Err(RecvTimeoutError::Timeout) => break false,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        };
+
+        if !answered {
+            println!(
+                "[TIMEOUT] Connection closed after {} seconds of inactivity",
+                timeout + PONG_TIMEOUT.as_secs()
+            );
+            let _ = writer.lock().unwrap().shutdown();
+            return;
+        }
+    }
+}
+
+// ==========================================
+// 8. MESURE DE LATENCE (--latency)
+// ==========================================
+
+// Avant le chat, sur le flux encore synchrone (aucun thread lancé) :
+// le client envoie LATENCY_SAMPLES pings chiffrés, le serveur les renvoie tels quels.
+// Les trames passent par le même chemin que les messages (HMAC, séquence, keystream).
+
+/// Reçoit une trame scellée du type attendu et renvoie son contenu déchiffré
+fn recv_sealed(stream: &mut dyn Transport, receiving: &mut Direction, expected: MessageType) -> Result<Vec<u8>, ChatError> {
+    let data = recv_framed(stream)?;
+    if !receiving.verify(&data) {
+        return Err(ChatError::AuthenticationFailed);
+    }
+    let seq = u64::from_be_bytes(data[1..9].try_into().unwrap());
+    if !check_and_advance_seq(&mut receiving.seq, seq) {
+        return Err(ChatError::ParseError(format!("replayed or out-of-order frame (seq={})", seq)));
+    }
+    if data[0] != expected as u8 {
+        return Err(ChatError::ParseError(format!("unexpected message type 0x{:02x}", data[0])));
+    }
+    Ok(receiving.cipher.apply(&data[9..data.len() - 32]))
+}
+
+fn measure_latency(stream: &mut dyn Transport, sending: &mut Direction, receiving: &mut Direction) -> Result<LatencyStats, ChatError> {
+    println!("[LATENCY] Measuring round-trip time ({} pings)...", LATENCY_SAMPLES);
+    let mut samples = Vec::with_capacity(LATENCY_SAMPLES);
+
+    for i in 0..LATENCY_SAMPLES as u64 {
+        let payload = i.to_be_bytes();
+        let start = Instant::now();
+        let encrypted = sending.cipher.apply(&payload);
+        send_framed(stream, &sending.seal(MessageType::LatencyPing, &encrypted))?;
+        let echo = recv_sealed(stream, receiving, MessageType::LatencyEcho)?;
+        samples.push(start.elapsed().as_micros() as u64);
+
+        if echo != payload {
+            return Err(ChatError::ParseError(format!("echo {} does not match ping", i)));
+        }
+    }
+    Ok(LatencyStats::new(&samples))
+}
+
+fn echo_latency(stream: &mut dyn Transport, sending: &mut Direction, receiving: &mut Direction) -> Result<(), ChatError> {
+    println!("[LATENCY] Echoing {} pings for the peer's measurement...", LATENCY_SAMPLES);
+    for _ in 0..LATENCY_SAMPLES {
+        let payload = recv_sealed(stream, receiving, MessageType::LatencyPing)?;
+        let encrypted = sending.cipher.apply(&payload);
+        send_framed(stream, &sending.seal(MessageType::LatencyEcho, &encrypted))?;
+    }
+    println!("[LATENCY] Done\n");
+    Ok(())
+}
+
+/// Statistiques d'aller-retour, en microsecondes
+struct LatencyStats {
+    /// Échantillons triés par ordre croissant
+    sorted: Vec<u64>,
+}
+
+impl LatencyStats {
+    fn new(samples: &[u64]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        LatencyStats { sorted }
+    }
+
+    fn mean(&self) -> f64 {
+        if self.sorted.is_empty() {
+            return 0.0;
+        }
+        self.sorted.iter().sum::<u64>() as f64 / self.sorted.len() as f64
+    }
+
+    fn median(&self) -> u64 {
+        self.percentile(50)
+    }
+
+    fn p95(&self) -> u64 {
+        self.percentile(95)
+    }
+
+    fn p99(&self) -> u64 {
+        self.percentile(99)
+    }
+
+    /// Rang le plus proche : plus petite valeur couvrant `p` % des échantillons
+    fn percentile(&self, p: usize) -> u64 {
+        if self.sorted.is_empty() {
+            return 0;
+        }
+        let rank = (p * self.sorted.len()).div_ceil(100).max(1);
+        self.sorted[rank - 1]
+    }
+
+    fn print(&self) {
+        println!("\n[LATENCY] Round-trip time over {} pings", self.sorted.len());
+        println!("+--------+--------------+");
+        println!("| Stat   |    RTT (µs)  |");
+        println!("+--------+--------------+");
+        println!("| mean   | {:>12.1} |", self.mean());
+        println!("| median | {:>12} |", self.median());
+        println!("| p95    | {:>12} |", self.p95());
+        println!("| p99    | {:>12} |", self.p99());
+        println!("+--------+--------------+\n");
+    }
+}
+
+// ==========================================
+// 9. AFFICHAGE DE LA CONVERSATION
+// ==========================================
+
+const ANSI_GREEN: &str = "\x1b[32m";
+const ANSI_CYAN: &str = "\x1b[36m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Résumé lisible de la conversation, intercalé avec le détail du chiffrement :
+/// [YOU] en vert pour nos messages (sauf --no-echo), [PEER] en cyan pour ceux reçus
+#[derive(Clone, Copy)]
+struct ChatRenderer {
+    echo: bool,
+}
+
+impl ChatRenderer {
+    fn print_sent(&self, msg: &str) {
+        if self.echo {
+            println!("{}[YOU]{} {}", ANSI_GREEN, ANSI_RESET, msg);
+        }
+    }
+
+    fn print_received(&self, msg: &str) {
+        println!("{}[PEER]{} {}", ANSI_CYAN, ANSI_RESET, msg);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_passwords_give_matching_tokens() {
+        assert_eq!(derive_auth_token(0x1234_5678_9abc_def0, "hunter2"), derive_auth_token(0x1234_5678_9abc_def0, "hunter2"));
+    }
+
+    #[test]
+    fn different_passwords_give_different_tokens() {
+        assert_ne!(derive_auth_token(0x1234_5678_9abc_def0, "hunter2"), derive_auth_token(0x1234_5678_9abc_def0, "hunter3"));
+        // Même mot de passe, autre session : jeton différent
+        assert_ne!(derive_auth_token(1, "hunter2"), derive_auth_token(2, "hunter2"));
+    }
+}
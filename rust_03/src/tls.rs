@@ -0,0 +1,319 @@
+// ==========================================
+// MODE TLS (--tls)
+// ==========================================
+
+// Remplace entièrement la négociation DH + keystream maison par une session rustls
+// en TLS 1.3. Le serveur génère un certificat auto-signé au démarrage : aucune AC
+// ne le garantit, le client affiche donc son empreinte pour vérification manuelle.
+
+use crate::error::ChatError;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{self, CryptoProvider};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName, UnixTime};
+use rustls::{ClientConfig, ClientConnection, DigitallySignedStruct, ServerConfig, ServerConnection, StreamOwned};
+use sha2::{Digest, Sha256};
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use std::process;
+
+// Le socket est relu régulièrement pour pouvoir aussi envoyer : StreamOwned ne se
+// partage pas entre deux threads comme un TcpStream cloné
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Un pair muet pendant la poignée de main ne garde pas sa place de --max-clients
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn provider() -> Arc<CryptoProvider> {
+    Arc::new(crypto::ring::default_provider())
+}
+
+/// Empreinte SHA-256 d'un certificat, au format AA:BB:...
+fn fingerprint(cert: &CertificateDer<'_>) -> String {
+    Sha256::digest(cert.as_ref())
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn tls_error(e: rustls::Error) -> ChatError {
+    ChatError::IoError(io::Error::other(e))
+}
+
+/// Lignes tapées sur stdin, diffusées à chaque session ouverte. Un seul thread lit stdin :
+/// un lecteur par session se disputerait les lignes, et celui d'une session terminée
+/// en avalerait encore une.
+struct StdinBroadcast {
+    /// Un émetteur par session ; None une fois stdin fermé
+    subscribers: Mutex<Option<Vec<mpsc::Sender<String>>>>,
+}
+
+impl StdinBroadcast {
+    fn start() -> Arc<Self> {
+        let hub = Arc::new(StdinBroadcast { subscribers: Mutex::new(Some(Vec::new())) });
+        let reader = Arc::clone(&hub);
+        thread::spawn(move || {
+            for line in io::stdin().lines().map_while(Result::ok) {
+                if let Some(subscribers) = reader.subscribers.lock().unwrap().as_mut() {
+                    // Session terminée : son récepteur a été droppé, on l'oublie
+                    subscribers.retain(|tx| tx.send(line.clone()).is_ok());
+                }
+            }
+            // Fin de stdin : chaque session voit son canal se fermer
+            *reader.subscribers.lock().unwrap() = None;
+        });
+        hub
+    }
+
+    /// Canal des prochaines lignes de stdin (déjà fermé si stdin est terminé)
+    fn subscribe(&self) -> mpsc::Receiver<String> {
+        let (tx, rx) = mpsc::channel();
+        if let Some(subscribers) = self.subscribers.lock().unwrap().as_mut() {
+            subscribers.push(tx);
+        }
+        rx
+    }
+}
+
+// ------------------------------------------
+// Serveur
+// ------------------------------------------
+
+/// Certificat auto-signé (CN localhost) et configuration TLS 1.3 du serveur
+fn server_config() -> Result<(ServerConfig, String), ChatError> {
+    let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()])
+        .map_err(|e| ChatError::IoError(io::Error::other(e)))?;
+    let cert = certified.cert.der().clone();
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(certified.key_pair.serialize_der()));
+    let fp = fingerprint(&cert);
+
+    let config = ServerConfig::builder_with_provider(provider())
+        .with_protocol_versions(&[&rustls::version::TLS13])
+        .map_err(tls_error)?
+        .with_no_client_auth()
+        .with_single_cert(vec![cert], key)
+        .map_err(tls_error)?;
+    Ok((config, fp))
+}
+
+pub fn start_server(addr: SocketAddr, max_clients: usize) {
+    let (config, fp) = match server_config() {
+        Ok(setup) => setup,
+        Err(e) => {
+            eprintln!("Error: Could not create TLS certificate. {}", e);
+            process::exit(1);
+        }
+    };
+    let config = Arc::new(config);
+
+    let listener = match TcpListener::bind(addr) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("Error: Could not bind to {}. {}", addr, e);
+            process::exit(1);
+        }
+    };
+
+    println!("[SERVER] Listening on {} (TLS)", addr);
+    println!("[TLS] Self-signed certificate fingerprint (SHA-256):");
+    println!("      {}", fp);
+    println!("[SERVER] Waiting for clients (max {})...\n", max_clients);
+
+    let active = Arc::new(AtomicUsize::new(0));
+    let mut next_id = 1;
+    // Chaque ligne tapée côté serveur part vers tous les clients connectés
+    let stdin = StdinBroadcast::start();
+
+    for incoming in listener.incoming() {
+        let stream = match incoming {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("[SERVER] Accept error: {}", e);
+                continue;
+            }
+        };
+
+        // Pas d'octet d'accueil en TLS : un client en trop est simplement fermé
+        if active.load(Ordering::SeqCst) >= max_clients {
+            let addr = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+            println!("[SERVER] Rejecting {}: server full ({} clients)", addr, max_clients);
+            continue;
+        }
+        active.fetch_add(1, Ordering::SeqCst);
+        let id = next_id;
+        next_id += 1;
+
+        let config = Arc::clone(&config);
+        let active = Arc::clone(&active);
+        let lines = stdin.subscribe();
+        thread::spawn(move || {
+            let result = ServerConnection::new(config)
+                .map_err(tls_error)
+                .and_then(|conn| run_session(StreamOwned::new(conn, stream), lines));
+            if let Err(e) = result {
+                eprintln!("[#{}] TLS error: {}", id, e);
+            }
+            let remaining = active.fetch_sub(1, Ordering::SeqCst) - 1;
+            println!("[#{}] Client disconnected ({} active)", id, remaining);
+        });
+    }
+}
+
+// ------------------------------------------
+// Client
+// ------------------------------------------
+
+/// Accepte n'importe quel certificat après avoir affiché son empreinte :
+/// c'est à l'utilisateur de la comparer à celle affichée par le serveur.
+/// Les signatures de la poignée de main restent vérifiées normalement.
+#[derive(Debug)]
+struct FingerprintVerifier {
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for FingerprintVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        println!("[TLS] Server certificate fingerprint (SHA-256):");
+        println!("      {}", fingerprint(end_entity));
+        println!("[TLS] Compare it with the fingerprint printed by the server");
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls12_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls13_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.provider.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+pub fn start_client(host: &str) {
+    println!("[CLIENT] Connecting to {} (TLS)...", host);
+    if let Err(e) = connect(host) {
+        eprintln!("Error: Failed to connect to {}. {}", host, e);
+        process::exit(1);
+    }
+}
+
+fn connect(host: &str) -> Result<(), ChatError> {
+    // Nom attendu par rustls : la partie hôte de "hôte:port" (nom DNS ou IP)
+    let name = host.rsplit_once(':').map_or(host, |(name, _)| name);
+    let name = ServerName::try_from(name.trim_matches(['[', ']']).to_string())
+        .map_err(|e| ChatError::ParseError(format!("invalid server name '{}': {}", name, e)))?;
+
+    let provider = provider();
+    let config = ClientConfig::builder_with_provider(Arc::clone(&provider))
+        .with_protocol_versions(&[&rustls::version::TLS13])
+        .map_err(tls_error)?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(FingerprintVerifier { provider }))
+        .with_no_client_auth();
+
+    let addrs: Vec<SocketAddr> = host.to_socket_addrs()?.collect();
+    let stream = TcpStream::connect(&addrs[..])?;
+    println!("[CLIENT] Connected!");
+    let conn = ClientConnection::new(Arc::new(config), name).map_err(tls_error)?;
+    run_session(StreamOwned::new(conn, stream), StdinBroadcast::start().subscribe())?;
+    println!("Peer disconnected.");
+    Ok(())
+}
+
+// ------------------------------------------
+// Session de chat
+// ------------------------------------------
+
+/// Poignée de main puis boucle de chat : une ligne de texte par message (lue sur `lines`),
+/// rustls se charge du chiffrement et de l'authentification
+fn run_session<C, S>(mut tls: StreamOwned<C, TcpStream>, lines: mpsc::Receiver<String>) -> Result<(), ChatError>
+where
+    C: std::ops::DerefMut<Target = rustls::ConnectionCommon<S>>,
+    S: rustls::SideData,
+{
+    tls.sock.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    while tls.conn.is_handshaking() {
+        tls.conn.complete_io(&mut tls.sock).map_err(|e| match e.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+                io::Error::new(io::ErrorKind::TimedOut, "TLS handshake timed out")
+            }
+            _ => e,
+        })?;
+    }
+    let suite = tls
+        .conn
+        .negotiated_cipher_suite()
+        .map(|s| format!("{:?}", s.suite()))
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("[TLS] TLS 1.3 session established ({})", suite);
+    println!("[CHAT] Type messages, Enter to send.\n");
+
+    tls.sock.set_read_timeout(Some(POLL_INTERVAL))?;
+
+    // Lignes de stdin consommées entre deux lectures du socket
+    let mut pending = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        loop {
+            match lines.try_recv() {
+                Ok(line) => {
+                    let msg = line.trim();
+                    if msg.is_empty() {
+                        continue;
+                    }
+                    tls.write_all(format!("{}\n", msg).as_bytes())?;
+                    tls.flush()?;
+                    println!("[->] Sent {} bytes over TLS\n", msg.len());
+                }
+                Err(TryRecvError::Empty) => break,
+                // Fin de stdin : on prévient le pair (close_notify) et on s'arrête
+                Err(TryRecvError::Disconnected) => {
+                    tls.conn.send_close_notify();
+                    let _ = tls.flush();
+                    return Ok(());
+                }
+            }
+        }
+
+        match tls.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => {
+                pending.extend_from_slice(&buf[..n]);
+                while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+                    let line: Vec<u8> = pending.drain(..=end).collect();
+                    println!("[<-] {}", String::from_utf8_lossy(&line[..end]));
+                    println!();
+                }
+            }
+            Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {}
+            // Fermeture TCP sans close_notify : le pair est parti
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
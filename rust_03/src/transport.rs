@@ -0,0 +1,99 @@
+// ==========================================
+// TRANSPORT (TCP OU SOCKET UNIX)
+// ==========================================
+
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+/// Flux d'octets sur lequel tourne la session (poignée de main, trames, heartbeat) :
+/// le code de chat ne dépend pas du type de socket sous-jacent
+pub trait Transport: Read + Write + Send {
+    /// Second accès au même flux, pour lire et écrire depuis deux threads
+    fn try_clone_transport(&self) -> io::Result<Box<dyn Transport>>;
+
+    /// Ferme les deux sens : débloque un éventuel read() en cours
+    fn shutdown(&self) -> io::Result<()>;
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+
+    /// Description du pair pour les messages de connexion
+    fn peer_label(&self) -> String;
+}
+
+pub struct TcpTransport(pub TcpStream);
+
+pub struct UnixTransport(pub UnixStream);
+
+impl Transport for TcpTransport {
+    fn try_clone_transport(&self) -> io::Result<Box<dyn Transport>> {
+        Ok(Box::new(TcpTransport(self.0.try_clone()?)))
+    }
+
+    fn shutdown(&self) -> io::Result<()> {
+        self.0.shutdown(Shutdown::Both)
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.0.set_read_timeout(timeout)
+    }
+
+    fn peer_label(&self) -> String {
+        self.0.peer_addr().map_or_else(|_| "unknown".to_string(), |addr| addr.to_string())
+    }
+}
+
+impl Transport for UnixTransport {
+    fn try_clone_transport(&self) -> io::Result<Box<dyn Transport>> {
+        Ok(Box::new(UnixTransport(self.0.try_clone()?)))
+    }
+
+    fn shutdown(&self) -> io::Result<()> {
+        self.0.shutdown(Shutdown::Both)
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.0.set_read_timeout(timeout)
+    }
+
+    // Côté serveur le socket du client n'a en général pas de chemin
+    fn peer_label(&self) -> String {
+        match self.0.peer_addr().ok().as_ref().and_then(|addr| addr.as_pathname()) {
+            Some(path) => format!("unix:{}", path.display()),
+            None => "unix socket".to_string(),
+        }
+    }
+}
+
+impl Read for TcpTransport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for TcpTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl Read for UnixTransport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for UnixTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
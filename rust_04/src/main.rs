@@ -1,10 +1,21 @@
-use clap::Parser;
-use rand::Rng; // Nécessaire pour .random()
+use clap::parser::ValueSource;
+use clap::{ArgAction, ArgMatches, CommandFactory, FromArgMatches, Parser, ValueEnum};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use image::DynamicImage;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng}; // Nécessaire pour .random() et seed_from_u64
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap};
-use std::fs;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::process; // Pour exit(1)
 
 // ==========================================
@@ -15,21 +26,33 @@ use std::process; // Pour exit(1)
 #[command(name = "hexpath", version, about = "Find min/max cost paths in hexadecimal grid")]
 struct Args {
     /// Map file (hex values, space separated)
-    #[arg(required_unless_present = "generate")]
+    #[arg(required_unless_present_any = ["generate", "image"])]
     file: Option<String>,
 
+    /// Use a PNG image as the map (grayscale luma = cell value)
+    #[arg(long, conflicts_with_all = ["file", "generate"])]
+    image: Option<String>,
+
     /// Generate random map (e.g., 8x4, 10x10)
     #[arg(long)]
     generate: Option<String>,
 
-    /// Save generated map to file
+    /// Save generated map to file (or the map with its --set overrides)
     #[arg(long)]
     output: Option<String>,
 
+    /// Override a cell before pathfinding, as "x,y,HH" (repeatable)
+    #[arg(long = "set", value_name = "X,Y,HH", value_parser = parse_override, action = ArgAction::Append)]
+    set: Vec<CellOverride>,
+
     /// Show colored map
     #[arg(long)]
     visualize: bool,
 
+    /// Label rows and columns of the --visualize grid (every 5 on grids over 100 cells wide or high)
+    #[arg(long)]
+    annotate: bool,
+
     /// Show both min and max paths
     #[arg(long)]
     both: bool,
@@ -37,6 +60,318 @@ struct Args {
     /// Animate pathfinding
     #[arg(long)]
     animate: bool,
+
+    /// Save every explored search state as frame_<N>.txt in this directory
+    #[arg(long, value_name = "DIR")]
+    visualize_steps: Option<PathBuf>,
+
+    /// Seed for --generate (same seed and size give the same map)
+    #[arg(long, requires = "generate")]
+    seed: Option<u64>,
+
+    /// Generate a maze (corridors 00, walls FF); odd sizes, rounded up if needed
+    #[arg(long, requires = "generate", conflicts_with = "distribution")]
+    maze_gen: bool,
+
+    /// Treat FF cells as walls (the end cell stays reachable)
+    #[arg(long)]
+    obstacles: bool,
+
+    /// Cells with a value above N are impassable (start and end excepted)
+    #[arg(long, value_name = "N")]
+    max_cell_cost: Option<u8>,
+
+    /// Cells with a value below N are impassable (start and end excepted)
+    #[arg(long, value_name = "N")]
+    min_cell_cost: Option<u8>,
+
+    /// Search only the subgrid "x1,y1,x2,y2", from (x1,y1) to (x2,y2)
+    #[arg(long, value_name = "X1,Y1,X2,Y2", value_parser = parse_region)]
+    region: Option<Region>,
+
+    /// Multi-source search from "x1,y1;x2,y2;...": farthest cell from every start and minimax center
+    #[arg(long, value_name = "X1,Y1;X2,Y2;...", value_parser = parse_starts,
+          conflicts_with_all = ["benchmark", "interactive", "animate"])]
+    multi_start: Option<StartCells>,
+
+    /// Value distribution of generated cells
+    #[arg(long, value_enum, default_value_t = DistributionKind::Uniform, requires = "generate")]
+    distribution: DistributionKind,
+
+    /// Mean cell value (with --distribution normal)
+    #[arg(long, default_value_t = 128.0)]
+    mean: f64,
+
+    /// Standard deviation of cell values (with --distribution normal)
+    #[arg(long, default_value_t = 48.0)]
+    stddev: f64,
+
+    /// How the cost of a move between two cells is computed
+    #[arg(long, value_enum, default_value_t = CostModel::Node)]
+    cost_model: CostModel,
+
+    /// Cell color palette for --visualize
+    #[arg(long, value_enum, default_value_t = ColorScheme::Rainbow)]
+    color_scheme: ColorScheme,
+
+    /// Time N runs of the minimum-path search and print only timing statistics
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..),
+          conflicts_with_all = ["animate", "visualize"])]
+    benchmark: Option<u32>,
+
+    /// Print search statistics (nodes enqueued, popped, skipped, relaxed)
+    #[arg(long)]
+    profile: bool,
+
+    /// Rescale the map file values linearly so they span 00..FF
+    #[arg(long)]
+    normalize: bool,
+
+    /// Swap rows and columns before pathfinding (cell (x,y) moves to (y,x))
+    #[arg(long)]
+    transpose: bool,
+
+    /// Mirror the grid left-right before pathfinding
+    #[arg(long)]
+    flip_h: bool,
+
+    /// Mirror the grid top-bottom before pathfinding
+    #[arg(long)]
+    flip_v: bool,
+
+    /// Rotate the grid by 180 degrees before pathfinding
+    #[arg(long)]
+    rotate_180: bool,
+
+    /// Replace every cell by 255 - value before pathfinding
+    #[arg(long)]
+    invert: bool,
+
+    /// Toroidal grid: moves across an edge come back on the opposite side
+    #[arg(long)]
+    wrap: bool,
+
+    /// Write the grid graph in Graphviz DOT format, minimum path in red
+    #[arg(long, value_name = "FILE")]
+    export_dot: Option<PathBuf>,
+
+    /// Export every cell with --export-dot, not only the path neighborhood
+    #[arg(long, requires = "export_dot")]
+    dot_full: bool,
+
+    /// Print cell value statistics (min, max, mean) of the map file
+    #[arg(long)]
+    stats: bool,
+
+    /// Append the search statistics as a CSV row to this file
+    #[arg(long, value_name = "FILE")]
+    profile_csv: Option<PathBuf>,
+
+    /// Remove path cells whose neighbors are directly connected at no extra cost
+    #[arg(long)]
+    smooth: bool,
+
+    /// Count the distinct minimum cost paths from start to end
+    #[arg(long)]
+    count_paths: bool,
+
+    /// Walk the grid yourself with the arrow keys and compare with the optimal path
+    #[arg(long, conflicts_with_all = ["animate", "benchmark"])]
+    interactive: bool,
+
+    /// Let --interactive walks step on an already visited cell
+    #[arg(long, requires = "interactive")]
+    allow_revisit: bool,
+
+    /// Shortest-path algorithm (bellman-ford is limited to 100x100 grids)
+    #[arg(long, value_enum, default_value_t = Algorithm::Dijkstra,
+          conflicts_with_all = ["animate", "visualize_steps", "benchmark", "profile", "profile_csv"])]
+    algorithm: Algorithm,
+
+    /// Load default settings from a TOML file (keys are flag names, e.g. color_scheme = "fire");
+    /// flags given on the command line take precedence
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Write the effective settings (--config merged with the flags) to a TOML file
+    #[arg(long, value_name = "FILE")]
+    save_config: Option<PathBuf>,
+}
+
+/// Algorithme de recherche du chemin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum Algorithm {
+    /// Priority queue, non-negative costs only
+    Dijkstra,
+    /// |V|-1 relaxation passes over every edge (educational comparison)
+    BellmanFord,
+}
+
+/// Valeur imposée à une cellule (--set)
+#[derive(Debug, Clone, Copy)]
+struct CellOverride {
+    x: usize,
+    y: usize,
+    value: u8,
+}
+
+fn parse_override(s: &str) -> Result<CellOverride, String> {
+    let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+    let [x, y, value] = parts[..] else {
+        return Err(format!("expected \"x,y,HH\", got \"{}\"", s));
+    };
+    let x = x.parse().map_err(|_| format!("invalid x coordinate \"{}\"", x))?;
+    let y = y.parse().map_err(|_| format!("invalid y coordinate \"{}\"", y))?;
+    let value = u8::from_str_radix(value.trim_start_matches("0x"), 16)
+        .map_err(|_| format!("invalid hex byte \"{}\"", value))?;
+    Ok(CellOverride { x, y, value })
+}
+
+/// Rectangle de recherche (--region), coins inclus
+#[derive(Debug, Clone, Copy)]
+struct Region {
+    x1: usize,
+    y1: usize,
+    x2: usize,
+    y2: usize,
+}
+
+fn parse_region(s: &str) -> Result<Region, String> {
+    let coords = s
+        .split(',')
+        .map(|part| part.trim().parse::<usize>().map_err(|_| format!("invalid coordinate \"{}\"", part)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let [x1, y1, x2, y2] = coords[..] else {
+        return Err(format!("expected \"x1,y1,x2,y2\", got \"{}\"", s));
+    };
+    if x1 > x2 || y1 > y2 {
+        return Err("(x1,y1) must be the top-left corner and (x2,y2) the bottom-right one".to_string());
+    }
+    Ok(Region { x1, y1, x2, y2 })
+}
+
+/// Cellules de départ de --multi-start
+#[derive(Debug, Clone)]
+struct StartCells(Vec<(usize, usize)>);
+
+fn parse_starts(s: &str) -> Result<StartCells, String> {
+    let starts = s
+        .split(';')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (x, y) = pair.split_once(',').ok_or_else(|| format!("expected \"x,y\", got \"{}\"", pair))?;
+            let x = x.trim().parse().map_err(|_| format!("invalid x coordinate \"{}\"", x.trim()))?;
+            let y = y.trim().parse().map_err(|_| format!("invalid y coordinate \"{}\"", y.trim()))?;
+            Ok((x, y))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    if starts.is_empty() {
+        return Err("at least one \"x,y\" start cell is required".to_string());
+    }
+    Ok(StartCells(starts))
+}
+
+/// Loi des valeurs générées par --generate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum DistributionKind {
+    /// Every value equally likely
+    Uniform,
+    /// Gaussian around --mean with --stddev, clamped to 00..FF
+    Normal,
+    /// Regions around random centers sharing similar values
+    Clustered,
+}
+
+/// Distribution effective (paramètres de la loi normale inclus)
+#[derive(Debug, Clone, Copy)]
+enum Distribution {
+    Uniform,
+    Normal { mean: f64, stddev: f64 },
+    Clustered,
+    Maze,
+}
+
+/// Coût d'un déplacement entre deux cellules voisines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum CostModel {
+    /// Value of the destination cell
+    Node,
+    /// Average of the source and destination values
+    EdgeAvg,
+    /// Larger of the source and destination values
+    EdgeMax,
+}
+
+impl CostModel {
+    fn name(self) -> &'static str {
+        match self {
+            CostModel::Node => "node",
+            CostModel::EdgeAvg => "edge-avg",
+            CostModel::EdgeMax => "edge-max",
+        }
+    }
+}
+
+fn compute_move_cost(model: CostModel, src_val: u8, dst_val: u8) -> u32 {
+    let (src, dst) = (src_val as u32, dst_val as u32);
+    match model {
+        CostModel::Node => dst,
+        CostModel::EdgeAvg => (src + dst) / 2,
+        CostModel::EdgeMax => src.max(dst),
+    }
+}
+
+/// Palette de couleurs des cellules (valeur 00..FF -> RGB)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum ColorScheme {
+    /// Red -> green -> blue gradient
+    Rainbow,
+    /// Black -> white
+    Grayscale,
+    /// Black -> red -> orange -> yellow -> white
+    Fire,
+    /// Blue -> white -> red
+    CoolWarm,
+}
+
+impl ColorScheme {
+    fn name(self) -> &'static str {
+        match self {
+            ColorScheme::Rainbow => "rainbow",
+            ColorScheme::Grayscale => "grayscale",
+            ColorScheme::Fire => "fire",
+            ColorScheme::CoolWarm => "cool-warm",
+        }
+    }
+
+    fn apply(&self, val: u8) -> (u8, u8, u8) {
+        match self {
+            ColorScheme::Rainbow => {
+                if val < 128 {
+                    let ratio = val as f32 / 128.0;
+                    let r = ((1.0 - ratio) * 255.0) as u8;
+                    let g = (ratio * 255.0) as u8;
+                    (r.saturating_add(50), g.saturating_add(50), 0)
+                } else {
+                    let ratio = (val - 128) as f32 / 127.0;
+                    let g = ((1.0 - ratio) * 255.0) as u8;
+                    let b = (ratio * 255.0) as u8;
+                    (0, g.saturating_add(50), b.saturating_add(50))
+                }
+            }
+            ColorScheme::Grayscale => (val, val, val),
+            ColorScheme::Fire => gradient(
+                &[(0, 0, 0), (255, 0, 0), (255, 128, 0), (255, 255, 0), (255, 255, 255)],
+                val,
+            ),
+            ColorScheme::CoolWarm => gradient(&[(0, 0, 255), (255, 255, 255), (255, 0, 0)], val),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -58,15 +393,63 @@ impl PartialOrd for State {
     }
 }
 
+/// Compteurs d'une recherche Dijkstra (--profile)
+#[derive(Debug, Clone, Copy, Default)]
+struct SearchStats {
+    /// Insertions dans le tas
+    enqueued: usize,
+    /// Sorties du tas
+    popped: usize,
+    /// Entrées périmées ignorées (cost > dist)
+    skipped: usize,
+    /// Distances améliorées
+    relaxed: usize,
+}
+
+#[derive(Clone)]
 struct Grid {
     width: usize,
     height: usize,
     cells: Vec<u8>,
+    /// Couleurs d'origine (grille chargée depuis une image)
+    colors: Option<Vec<(u8, u8, u8)>>,
+    /// Topologie torique (--wrap) : les bords opposés sont voisins
+    wrap: bool,
+    /// --obstacles : les cellules FF sont des murs
+    obstacles: bool,
+    /// --min-cell-cost / --max-cell-cost : valeurs hors de cet intervalle infranchissables
+    min_cell: u8,
+    max_cell: u8,
 }
 
 impl Grid {
     fn new(width: usize, height: usize, cells: Vec<u8>) -> Self {
-        Self { width, height, cells }
+        Self { width, height, cells, colors: None, wrap: false, obstacles: false, min_cell: 0, max_cell: 255 }
+    }
+
+    /// Une cellule par pixel : valeur = luminance, couleur d'origine conservée
+    fn from_image(img: &DynamicImage) -> Self {
+        let luma = img.to_luma8();
+        let rgb = img.to_rgb8();
+        let cells = luma.pixels().map(|p| p.0[0]).collect();
+        let colors = rgb.pixels().map(|p| (p.0[0], p.0[1], p.0[2])).collect();
+        Self {
+            width: img.width() as usize,
+            height: img.height() as usize,
+            cells,
+            colors: Some(colors),
+            wrap: false,
+            obstacles: false,
+            min_cell: 0,
+            max_cell: 255,
+        }
+    }
+
+    /// On peut entrer dans la cellule (l'arrivée l'est toujours, même à FF ou hors seuils)
+    fn is_passable(&self, idx: usize) -> bool {
+        let val = self.cells[idx];
+        idx == self.cells.len() - 1
+            || !(self.obstacles && val == 0xFF) && (self.min_cell..=self.max_cell).contains(&val)
     }
 
     fn get_index(&self, x: usize, y: usize) -> usize {
@@ -80,14 +463,333 @@ impl Grid {
     fn get_val(&self, x: usize, y: usize) -> u8 {
         self.cells[self.get_index(x, y)]
     }
+
+    /// Nouvelle grille width x height : la cellule (x, y) vient de source(x, y) dans self.
+    /// Les couleurs d'image suivent leurs cellules, les autres réglages sont conservés.
+    fn remap(&self, width: usize, height: usize, source: impl Fn(usize, usize) -> (usize, usize)) -> Grid {
+        let indices: Vec<usize> = (0..width * height)
+            .map(|i| {
+                let (sx, sy) = source(i % width, i / width);
+                self.get_index(sx, sy)
+            })
+            .collect();
+        Grid {
+            width,
+            height,
+            cells: indices.iter().map(|&i| self.cells[i]).collect(),
+            colors: self.colors.as_ref().map(|colors| indices.iter().map(|&i| colors[i]).collect()),
+            ..*self
+        }
+    }
+
+    /// Lignes et colonnes échangées : transpose().get_val(y, x) == get_val(x, y)
+    fn transpose(&self) -> Grid {
+        self.remap(self.height, self.width, |x, y| (y, x))
+    }
+
+    /// Miroir gauche-droite
+    fn flip_h(&self) -> Grid {
+        self.remap(self.width, self.height, |x, y| (self.width - 1 - x, y))
+    }
+
+    /// Miroir haut-bas
+    fn flip_v(&self) -> Grid {
+        self.remap(self.width, self.height, |x, y| (x, self.height - 1 - y))
+    }
+
+    /// Demi-tour : les deux miroirs (transposer deux fois redonnerait la grille d'origine)
+    fn rotate_180(&self) -> Grid {
+        self.flip_h().flip_v()
+    }
+}
+
+/// Génère une grille w x h reproductible : même graine => mêmes cellules
+fn generate_grid(w: usize, h: usize, seed: u64, dist: Distribution) -> Grid {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut cells = generate_cells(w, h, dist, &mut rng);
+
+    // Force Start (00) and End (FF)
+    cells[0] = 0x00;
+    cells[w * h - 1] = 0xFF;
+
+    Grid::new(w, h, cells)
+}
+
+// Écart maximal autour de la valeur d'un centre (--distribution clustered)
+const CLUSTER_NOISE: i32 = 12;
+
+fn generate_cells(w: usize, h: usize, dist: Distribution, rng: &mut impl Rng) -> Vec<u8> {
+    match dist {
+        Distribution::Uniform => (0..w * h).map(|_| rng.random()).collect(),
+        Distribution::Normal { mean, stddev } => (0..w * h)
+            .map(|_| {
+                // Box-Muller : deux uniformes -> une gaussienne centrée réduite
+                let u1: f64 = 1.0 - rng.random::<f64>();
+                let u2: f64 = rng.random();
+                let z = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+                (mean + z * stddev).round().clamp(0.0, 255.0) as u8
+            })
+            .collect(),
+        Distribution::Clustered => {
+            // sqrt(w*h)/2 centres, chaque cellule prend la valeur du plus proche (± bruit)
+            let count = (((w * h) as f64).sqrt() / 2.0).max(1.0) as usize;
+            let centers: Vec<(usize, usize, u8)> = (0..count)
+                .map(|_| (rng.random_range(0..w), rng.random_range(0..h), rng.random()))
+                .collect();
+
+            (0..w * h)
+                .map(|i| {
+                    let (x, y) = (i % w, i / w);
+                    let &(_, _, value) = centers
+                        .iter()
+                        .min_by_key(|&&(cx, cy, _)| cx.abs_diff(x).pow(2) + cy.abs_diff(y).pow(2))
+                        .unwrap();
+                    let noise = rng.random_range(-CLUSTER_NOISE..=CLUSTER_NOISE);
+                    (value as i32 + noise).clamp(0, 255) as u8
+                })
+                .collect()
+        }
+        Distribution::Maze => generate_maze(w, h, rng),
+    }
+}
+
+/// Labyrinthe par backtracking récursif (DFS, voisins tirés au hasard).
+/// Les couloirs (00) sont sur les coordonnées paires, les murs (FF) ailleurs :
+/// toutes les cases paires sont reliées, donc (0,0) et (w-1,h-1) aussi.
+fn generate_maze(w: usize, h: usize, rng: &mut impl Rng) -> Vec<u8> {
+    let mut cells = vec![0xFFu8; w * h];
+    cells[0] = 0x00;
+
+    // Pile explicite : pas de récursion profonde sur les grandes grilles
+    let mut stack = vec![(0usize, 0usize)];
+    while let Some(&(x, y)) = stack.last() {
+        let mut unvisited: Vec<(usize, usize)> = [(0isize, -2isize), (2, 0), (0, 2), (-2, 0)]
+            .iter()
+            .filter_map(|&(dx, dy)| {
+                let nx = x.checked_add_signed(dx)?;
+                let ny = y.checked_add_signed(dy)?;
+                (nx < w && ny < h && cells[ny * w + nx] == 0xFF).then_some((nx, ny))
+            })
+            .collect();
+
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let (nx, ny) = unvisited.swap_remove(rng.random_range(0..unvisited.len()));
+        // On creuse le mur entre les deux cases
+        cells[(y + ny) / 2 * w + (x + nx) / 2] = 0x00;
+        cells[ny * w + nx] = 0x00;
+        stack.push((nx, ny));
+    }
+    cells
+}
+
+// Une image plus grande est réduite pour tenir dans 255x255 (proportions conservées)
+const MAX_IMAGE_SIDE: u32 = 255;
+
+fn load_image_grid(path: &str) -> Result<Grid, String> {
+    let mut img = image::open(path).map_err(|e| format!("{}: {}", path, e))?;
+    if img.width() > MAX_IMAGE_SIDE || img.height() > MAX_IMAGE_SIDE {
+        img = img.resize(MAX_IMAGE_SIDE, MAX_IMAGE_SIDE, image::imageops::FilterType::Triangle);
+    }
+    if img.width() == 0 || img.height() == 0 {
+        return Err(format!("{}: empty image", path));
+    }
+    Ok(Grid::from_image(&img))
 }
 
 // ==========================================
 // MAIN LOGIC
 // ==========================================
 
+// ==========================================
+// CONFIGURATION PERSISTANTE (--config / --save-config)
+// ==========================================
+
+/// Réglages lus ou écrits en TOML, clés = noms des options en snake_case.
+/// Les entrées (fichier, --generate, --set...) et les modes ponctuels
+/// (--benchmark, --interactive, exports) restent sur la ligne de commande ;
+/// leurs modificateurs (seed, maze_gen, dot_full, allow_revisit) sont gardés
+/// et ne servent que si le mode est demandé.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    visualize: Option<bool>,
+    annotate: Option<bool>,
+    both: Option<bool>,
+    animate: Option<bool>,
+    seed: Option<u64>,
+    maze_gen: Option<bool>,
+    obstacles: Option<bool>,
+    max_cell_cost: Option<u8>,
+    min_cell_cost: Option<u8>,
+    distribution: Option<DistributionKind>,
+    mean: Option<f64>,
+    stddev: Option<f64>,
+    cost_model: Option<CostModel>,
+    color_scheme: Option<ColorScheme>,
+    profile: Option<bool>,
+    normalize: Option<bool>,
+    transpose: Option<bool>,
+    flip_h: Option<bool>,
+    flip_v: Option<bool>,
+    rotate_180: Option<bool>,
+    invert: Option<bool>,
+    wrap: Option<bool>,
+    dot_full: Option<bool>,
+    stats: Option<bool>,
+    smooth: Option<bool>,
+    count_paths: Option<bool>,
+    allow_revisit: Option<bool>,
+    algorithm: Option<Algorithm>,
+}
+
+impl Config {
+    /// Réglages effectifs, pour --save-config
+    fn from_args(args: &Args) -> Self {
+        Config {
+            visualize: Some(args.visualize),
+            annotate: Some(args.annotate),
+            both: Some(args.both),
+            animate: Some(args.animate),
+            seed: args.seed,
+            maze_gen: Some(args.maze_gen),
+            obstacles: Some(args.obstacles),
+            max_cell_cost: args.max_cell_cost,
+            min_cell_cost: args.min_cell_cost,
+            distribution: Some(args.distribution),
+            mean: Some(args.mean),
+            stddev: Some(args.stddev),
+            cost_model: Some(args.cost_model),
+            color_scheme: Some(args.color_scheme),
+            profile: Some(args.profile),
+            normalize: Some(args.normalize),
+            transpose: Some(args.transpose),
+            flip_h: Some(args.flip_h),
+            flip_v: Some(args.flip_v),
+            rotate_180: Some(args.rotate_180),
+            invert: Some(args.invert),
+            wrap: Some(args.wrap),
+            dot_full: Some(args.dot_full),
+            stats: Some(args.stats),
+            smooth: Some(args.smooth),
+            count_paths: Some(args.count_paths),
+            allow_revisit: Some(args.allow_revisit),
+            algorithm: Some(args.algorithm),
+        }
+    }
+}
+
+fn load_config(path: &Path) -> Result<Config, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    toml::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn save_config(args: &Args, path: &Path) -> Result<(), String> {
+    let content = toml::to_string(&Config::from_args(args)).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Applique les valeurs du fichier sauf pour les options passées sur la ligne de commande.
+/// Renvoie les options dont la valeur a changé.
+fn merge_config(args: &mut Args, config: Config, matches: &ArgMatches) -> Vec<&'static str> {
+    let from_cli = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+    let mut changed = Vec::new();
+
+    macro_rules! merge {
+        ($($field:ident),* $(,)?) => {$(
+            if let Some(value) = config.$field
+                && !from_cli(stringify!($field))
+            {
+                let previous = std::mem::replace(&mut args.$field, value.into());
+                if previous != args.$field {
+                    changed.push(stringify!($field));
+                }
+            }
+        )*};
+    }
+    merge!(
+        visualize, annotate, both, animate, seed, maze_gen, obstacles, max_cell_cost, min_cell_cost,
+        distribution, mean, stddev, cost_model, color_scheme, profile, normalize, transpose, flip_h, flip_v,
+        rotate_180, invert, wrap, dot_full, stats, smooth, count_paths, allow_revisit, algorithm,
+    );
+    changed
+}
+
+/// Options qui ne servent qu'à produire la map : avec --generate, si rien d'autre
+/// n'est demandé, on s'arrête après l'avoir affichée ou sauvegardée
+const GENERATION_ARGS: &[&str] =
+    &["generate", "output", "seed", "maze_gen", "distribution", "mean", "stddev", "set", "config", "save_config"];
+
+/// Vrai si une option d'analyse vient de la ligne de commande ou de --config
+fn requests_analysis(matches: &ArgMatches, from_config: &[&str]) -> bool {
+    // Les arguments de la commande, pas matches.ids() qui contient aussi le groupe "Args"
+    let command = Args::command();
+    command
+        .get_arguments()
+        .map(|arg| arg.get_id().as_str())
+        .filter(|id| matches.value_source(id) == Some(ValueSource::CommandLine))
+        .chain(from_config.iter().copied())
+        .any(|id| !GENERATION_ARGS.contains(&id))
+}
+
+/// clap ne voit que la ligne de commande : on revérifie ses incompatibilités
+/// sur les réglages fusionnés avec --config
+fn check_merged_conflicts(args: &Args) -> Result<(), String> {
+    if args.algorithm != Algorithm::Dijkstra
+        && (args.animate || args.visualize_steps.is_some() || args.benchmark.is_some() || args.profile || args.profile_csv.is_some())
+    {
+        return Err("--algorithm bellman-ford cannot be used with --animate, --visualize-steps, --benchmark, --profile or --profile-csv".to_string());
+    }
+    if args.benchmark.is_some() && (args.animate || args.visualize) {
+        return Err("--benchmark cannot be used with --animate or --visualize".to_string());
+    }
+    if args.interactive && args.animate {
+        return Err("--interactive cannot be used with --animate".to_string());
+    }
+    if args.maze_gen && args.distribution != DistributionKind::Uniform {
+        return Err("--maze-gen cannot be used with --distribution".to_string());
+    }
+    Ok(())
+}
+
 fn main() {
-    let args = Args::parse();
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    // --config : réglages par défaut, la ligne de commande reste prioritaire
+    let mut from_config = Vec::new();
+    if let Some(path) = args.config.clone() {
+        let merged = load_config(&path).and_then(|config| {
+            from_config = merge_config(&mut args, config, &matches);
+            check_merged_conflicts(&args)
+        });
+        if let Err(e) = merged {
+            eprintln!("Error in config: {}", e);
+            process::exit(1);
+        }
+    }
+
+    // --save-config : réglages effectifs, avant tout traitement
+    if let Some(path) = &args.save_config {
+        if let Err(e) = save_config(&args, path) {
+            eprintln!("Error saving config: {}", e);
+            process::exit(1);
+        }
+        eprintln!("Configuration saved to {}", path.display());
+    }
+    let args = args;
+    // --benchmark : seules les statistiques finales vont sur stdout
+    let quiet = args.benchmark.is_some();
+
+    // --set : appliqué quelle que soit la source de la grille
+    let apply_overrides_or_exit = |grid: &mut Grid| {
+        if let Err(e) = apply_overrides(grid, &args.set, quiet) {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+    };
 
     // 1. GENERATION DE MAP
     // Note: on utilise &args.generate pour ne pas consommer 'args'
@@ -97,50 +799,82 @@ fn main() {
             eprintln!("Invalid format. Use WxH (e.g., 10x10)");
             process::exit(1); // CORRECTION : Exit code 1
         }
-        let w: usize = parts[0].parse().unwrap_or(10);
-        let h: usize = parts[1].parse().unwrap_or(10);
+        let mut w: usize = parts[0].parse().unwrap_or(10);
+        let mut h: usize = parts[1].parse().unwrap_or(10);
 
-        println!("Generating {}x{} hexadecimal grid...", w, h);
-        
-        // CORRECTION : Rand 0.9+ syntaxe
-        let mut rng = rand::rng();
-        let mut cells = vec![0u8; w * h];
-        
-        for i in 0..cells.len() {
-            cells[i] = rng.random(); 
+        // Le labyrinthe alterne couloirs et murs : dimensions impaires
+        if args.maze_gen && (w.is_multiple_of(2) || h.is_multiple_of(2)) {
+            w |= 1;
+            h |= 1;
+            eprintln!("Maze size rounded up to {}x{}", w, h);
         }
 
-        // Force Start (00) and End (FF)
-        cells[0] = 0x00;
-        cells[w * h - 1] = 0xFF;
+        if !quiet {
+            println!("Generating {}x{} hexadecimal grid...", w, h);
+        }
+
+        // Sans --seed, on tire une graine et on l'affiche pour pouvoir rejouer la map
+        let seed = args.seed.unwrap_or_else(|| {
+            let seed = rand::rng().random();
+            eprintln!("Seed: {} (use --seed {} to reproduce)", seed, seed);
+            seed
+        });
+        let dist = match args.distribution {
+            _ if args.maze_gen => Distribution::Maze,
+            DistributionKind::Uniform => Distribution::Uniform,
+            DistributionKind::Normal => Distribution::Normal { mean: args.mean, stddev: args.stddev },
+            DistributionKind::Clustered => Distribution::Clustered,
+        };
+        let mut grid = generate_grid(w, h, seed, dist);
+        apply_overrides_or_exit(&mut grid);
 
         // Affichage brut
-        print_grid_values(&cells, w);
+        if !quiet {
+            print_grid_values(&grid.cells, w);
+        }
 
         // Sauvegarde
         if let Some(out_file) = &args.output {
-            let mut content = String::new();
-            for (i, val) in cells.iter().enumerate() {
-                content.push_str(&format!("{:02X}", val));
-                if (i + 1) % w == 0 { content.push('\n'); } else { content.push(' '); }
-            }
-            if let Err(e) = fs::write(out_file, content) {
+            if let Err(e) = save_grid(&grid, out_file) {
                 eprintln!("Error writing file: {}", e);
                 process::exit(1); // CORRECTION : Exit code 1
-            } else {
+            } else if !quiet {
                 println!("Map saved to: {}", out_file);
             }
         }
 
-        if !args.visualize && !args.both && !args.animate {
+        if !requests_analysis(&matches, &from_config) {
             return;
         }
         
-        process_grid(Grid::new(w, h, cells), &args);
+        process_grid(grid, &args);
+        return;
+    }
+
+    // 2. IMAGE PNG
+    if let Some(image_path) = &args.image {
+        let mut grid = match load_image_grid(image_path) {
+            Ok(grid) => grid,
+            Err(e) => {
+                eprintln!("Could not load image: {}", e);
+                process::exit(1);
+            }
+        };
+        apply_overrides_or_exit(&mut grid);
+        save_overridden(&grid, &args);
+
+        if !quiet {
+            println!("Analyzing image grid...");
+            println!("Grid size: {}x{}", grid.width, grid.height);
+            println!("Start: (0,0) = 0x{:02X}", grid.cells[0]);
+            println!("End: ({},{}) = 0x{:02X}", grid.width - 1, grid.height - 1, grid.cells[grid.cells.len() - 1]);
+        }
+
+        process_grid(grid, &args);
         return;
     }
 
-    // 2. LECTURE DE FICHIER
+    // 3. LECTURE DE FICHIER
     if let Some(file_path) = &args.file {
         match fs::read_to_string(file_path) {
             Ok(content) => {
@@ -164,14 +898,33 @@ fn main() {
                     process::exit(1); // CORRECTION : Exit code 1
                 }
 
-                if args.generate.is_none() {
+                let original = (args.stats && !quiet).then(|| cell_stats(&cells));
+                if args.normalize && !normalize_cells(&mut cells) {
+                    eprintln!("Warning: all cells have the same value, --normalize skipped");
+                }
+
+                let mut grid = Grid::new(width, height, cells);
+                apply_overrides_or_exit(&mut grid);
+                save_overridden(&grid, &args);
+                let cells = &grid.cells;
+
+                if !quiet {
                     println!("Analyzing hexadecimal grid...");
                     println!("Grid size: {}x{}", width, height);
                     println!("Start: (0,0) = 0x{:02X}", cells[0]);
                     println!("End: ({},{}) = 0x{:02X}", width - 1, height - 1, cells[cells.len() - 1]);
                 }
 
-                process_grid(Grid::new(width, height, cells), &args);
+                if let Some(original) = original {
+                    if args.normalize {
+                        print_cell_stats("Original", original);
+                        print_cell_stats("Normalized", cell_stats(cells));
+                    } else {
+                        print_cell_stats("Cells", original);
+                    }
+                }
+
+                process_grid(grid, &args);
             }
             Err(e) => {
                 eprintln!("Could not read file: {}", e);
@@ -181,19 +934,158 @@ fn main() {
     }
 }
 
-fn process_grid(grid: Grid, args: &Args) {
+/// Applique les --set dans l'ordre (le dernier l'emporte sur une même cellule)
+fn apply_overrides(grid: &mut Grid, overrides: &[CellOverride], quiet: bool) -> Result<(), String> {
+    for o in overrides {
+        if o.x >= grid.width || o.y >= grid.height {
+            return Err(format!(
+                "--set ({},{}) is outside the {}x{} grid",
+                o.x, o.y, grid.width, grid.height
+            ));
+        }
+        let idx = grid.get_index(o.x, o.y);
+        grid.cells[idx] = o.value;
+        if !quiet {
+            println!("[OVERRIDE] ({},{}) := 0x{:02X}", o.x, o.y, o.value);
+        }
+    }
+    Ok(())
+}
+
+/// Une ligne par rangée, valeurs hex séparées par des espaces (format des fichiers de map)
+fn save_grid(grid: &Grid, path: &str) -> io::Result<()> {
+    let mut content = String::new();
+    for (i, val) in grid.cells.iter().enumerate() {
+        content.push_str(&format!("{:02X}", val));
+        if (i + 1) % grid.width == 0 { content.push('\n'); } else { content.push(' '); }
+    }
+    fs::write(path, content)
+}
+
+/// Fichier ou image modifiés par --set : sauvegardés si --output est donné
+fn save_overridden(grid: &Grid, args: &Args) {
+    if args.set.is_empty() {
+        return;
+    }
+    if let Some(out_file) = &args.output {
+        if let Err(e) = save_grid(grid, out_file) {
+            eprintln!("Error writing file: {}", e);
+            process::exit(1);
+        }
+        if args.benchmark.is_none() {
+            println!("Map saved to: {}", out_file);
+        }
+    }
+}
+
+fn process_grid(mut grid: Grid, args: &Args) {
+    let quiet = args.benchmark.is_some();
+
+    // Symétries, dans cet ordre ; combinées, elles couvrent les huit orientations
+    let mut applied = Vec::new();
+    if args.transpose {
+        grid = grid.transpose();
+        applied.push("transpose");
+    }
+    if args.flip_h {
+        grid = grid.flip_h();
+        applied.push("flip-h");
+    }
+    if args.flip_v {
+        grid = grid.flip_v();
+        applied.push("flip-v");
+    }
+    if args.rotate_180 {
+        grid = grid.rotate_180();
+        applied.push("rotate-180");
+    }
+    if !applied.is_empty() && !quiet {
+        println!("[TRANSFORM] {}: grid is now {}x{}", applied.join(", "), grid.width, grid.height);
+    }
+
+    // Les couleurs de l'image ne correspondent plus aux valeurs inversées
+    if args.invert {
+        invert_cells(&mut grid.cells);
+        grid.colors = None;
+    }
+    grid.wrap = args.wrap;
+    grid.obstacles = args.obstacles;
+    grid.min_cell = args.min_cell_cost.unwrap_or(0);
+    grid.max_cell = args.max_cell_cost.unwrap_or(255);
+
+    // --region : la recherche tourne sur le sous-rectangle, l'affichage reste sur la grille entière
+    let region = args.region.map(|Region { x1, y1, x2, y2 }| {
+        if x2 >= grid.width || y2 >= grid.height {
+            eprintln!("Error: --region ({},{})-({},{}) is outside the {}x{} grid", x1, y1, x2, y2, grid.width, grid.height);
+            process::exit(1);
+        }
+        let sub = extract_subgrid(&grid, x1, y1, x2, y2);
+        if !quiet {
+            println!("[REGION] Searching ({},{}) -> ({},{}), coordinates below are relative to the region", x1, y1, x2, y2);
+        }
+        (std::mem::replace(&mut grid, sub), x1, y1)
+    });
+    let show = |path: &[usize]| match &region {
+        Some((full, x1, y1)) => {
+            let translated: Vec<usize> = path
+                .iter()
+                .map(|&idx| {
+                    let (x, y) = grid.get_xy(idx);
+                    full.get_index(x + x1, y + y1)
+                })
+                .collect();
+            print_grid(full, &translated, args.color_scheme, args.annotate);
+        }
+        None => print_grid(&grid, path, args.color_scheme, args.annotate),
+    };
+
+    if let Some(runs) = args.benchmark {
+        run_benchmark(&grid, runs, args.cost_model);
+        return;
+    }
+
+    if args.interactive {
+        if let Err(e) = run_interactive(&grid, args.cost_model, args.allow_revisit) {
+            eprintln!("Interactive mode failed: {}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(StartCells(starts)) = &args.multi_start {
+        if let Some(&(x, y)) = starts.iter().find(|&&(x, y)| x >= grid.width || y >= grid.height) {
+            eprintln!("Error: --multi-start cell ({},{}) is outside the {}x{} grid", x, y, grid.width, grid.height);
+            process::exit(1);
+        }
+        run_multi_start(&grid, starts, args.cost_model);
+        return;
+    }
+
+    // Dossier des frames créé à la demande
+    let frames_dir = args.visualize_steps.as_deref();
+    if let Some(dir) = frames_dir
+        && let Err(e) = fs::create_dir_all(dir)
+    {
+        eprintln!("Could not create {}: {}", dir.display(), e);
+        process::exit(1);
+    }
+
     if args.visualize {
-        println!("\nHEXADECIMAL GRID (rainbow gradient):");
+        if grid.colors.is_some() {
+            println!("\nHEXADECIMAL GRID (image colors):");
+        } else {
+            println!("\nHEXADECIMAL GRID ({} gradient):", args.color_scheme.name());
+        }
         println!("========================================");
-        print_colored_grid(&grid, &[]);
+        show(&[]);
     }
 
     if args.animate {
         println!("\nSearching for minimum cost path...");
-        let (path, _cost) = find_path(&grid, false, true);
+        let (path, _cost, _) = find_path(&grid, false, true, args.cost_model, frames_dir);
         if let Some(p) = path {
              println!("\nStep {}: Path found!", p.len());
-             print_colored_grid(&grid, &p);
+             show(&p);
         }
         return; 
     }
@@ -201,57 +1093,272 @@ fn process_grid(grid: Grid, args: &Args) {
     // Calcul du chemin MIN
     println!("\nMINIMUM COST PATH:");
     println!("==================");
-    let (min_path, min_cost) = find_path(&grid, false, false);
+    if args.algorithm == Algorithm::BellmanFord
+        && (grid.width > BELLMAN_FORD_MAX_SIDE || grid.height > BELLMAN_FORD_MAX_SIDE)
+    {
+        eprintln!(
+            "Error: Bellman-Ford is limited to {}x{} grids (got {}x{})",
+            BELLMAN_FORD_MAX_SIDE, BELLMAN_FORD_MAX_SIDE, grid.width, grid.height
+        );
+        process::exit(1);
+    }
+
+    if args.invert {
+        println!("[INVERT] Cell values replaced by 255 - value");
+        if args.both {
+            println!("[INVERT] Minimum path on the inverted grid = maximum path on the original grid");
+        }
+    }
+    let (min_path, min_cost, stats) = match args.algorithm {
+        Algorithm::Dijkstra => find_path(&grid, false, false, args.cost_model, frames_dir),
+        Algorithm::BellmanFord => {
+            let (path, cost) = run_bellman_ford(&grid, false, args.cost_model);
+            (path, cost, SearchStats::default())
+        }
+    };
     
+    // --smooth : le chemin lissé remplace l'original pour l'affichage et l'export
+    let min_path = min_path.map(|path| {
+        if !args.smooth {
+            return path;
+        }
+        let smoothed = smooth_path(&path, &grid, args.cost_model);
+        println!(
+            "\nSmoothed path: {} steps, {} turns (original: {} steps, {} turns)",
+            smoothed.len(),
+            count_turns(&grid, &smoothed),
+            path.len(),
+            count_turns(&grid, &path)
+        );
+        smoothed
+    });
+
     if let Some(path) = &min_path {
-        print_path_stats(path, min_cost, &grid);
+        print_path_stats(path, min_cost, &grid, args.cost_model);
+        print_bounds(min_cost, &grid);
         if args.visualize {
              println!("\nMINIMUM COST PATH (shown in WHITE):");
              println!("===================================");
-             print_colored_grid(&grid, path);
+             show(path);
         }
     } else {
         println!("No path found!");
+        if args.max_cell_cost.is_some() {
+            match find_min_threshold(&grid) {
+                Some(t) => println!("A path exists with --max-cell-cost {} or higher", t),
+                None => println!("No --max-cell-cost would give a path"),
+            }
+        }
+        if args.min_cell_cost.is_some() {
+            match find_max_floor(&grid) {
+                Some(t) => println!("A path exists with --min-cell-cost {} or lower", t),
+                None => println!("No --min-cell-cost would give a path"),
+            }
+        }
+    }
+
+    if args.count_paths && min_path.is_some() {
+        let dist = shortest_distances(&grid, args.cost_model);
+        match count_shortest_paths(&grid, &dist, args.cost_model) {
+            None => println!("\nDistinct minimum cost paths: unbounded (zero-cost cycle on a minimum path)"),
+            Some(u128::MAX) => println!("\nDistinct minimum cost paths: >{}", u128::MAX),
+            Some(count) => println!("\nDistinct minimum cost paths: {}", count),
+        }
+    }
+
+    if args.profile {
+        print_search_stats(&stats);
+    }
+    if let Some(csv_path) = &args.profile_csv {
+        let cost = min_path.as_ref().map(|_| min_cost);
+        if let Err(e) = append_profile_csv(csv_path, &grid, &stats, cost) {
+            eprintln!("Error writing {}: {}", csv_path.display(), e);
+            process::exit(1);
+        }
+    }
+
+    if let Some(dot_path) = &args.export_dot {
+        let path = min_path.as_deref().unwrap_or(&[]);
+        let result = File::create(dot_path).and_then(|file| {
+            let mut out = io::BufWriter::new(file);
+            export_dot(&grid, path, args.dot_full, &mut out)?;
+            out.flush()
+        });
+        if let Err(e) = result {
+            eprintln!("Error writing {}: {}", dot_path.display(), e);
+            process::exit(1);
+        }
+        println!("\nDOT graph saved to: {}", dot_path.display());
+    }
+
+    if let Some(dir) = frames_dir {
+        println!("\nSearch frames saved to: {}", dir.display());
     }
 
     // Calcul du chemin MAX
     if args.both {
         println!("\nMAXIMUM COST PATH:");
         println!("==================");
-        let (max_path, _max_cost_inverted) = find_path(&grid, true, false);
+        let max_path = match args.algorithm {
+            Algorithm::Dijkstra => find_path(&grid, true, false, args.cost_model, None).0,
+            Algorithm::BellmanFord => run_bellman_ford(&grid, true, args.cost_model).0,
+        };
         
         if let Some(path) = &max_path {
-            print_path_stats(path, 0, &grid); 
+            print_path_stats(path, 0, &grid, args.cost_model); 
             
             if args.visualize {
                 println!("\nMAXIMUM COST PATH (shown in WHITE):");
-                print_colored_grid(&grid, path);
+                show(path);
             }
         }
     }
 }
 
-// ==========================================
-// ALGORITHME DIJKSTRA
-// ==========================================
+/// Chronomètre `runs` recherches du chemin minimum (après un tour de chauffe non mesuré)
+fn run_benchmark(grid: &Grid, runs: u32, model: CostModel) {
+    std::hint::black_box(find_path(grid, false, false, model, None));
 
-fn find_path(grid: &Grid, maximize: bool, animate: bool) -> (Option<Vec<usize>>, u32) {
-    let start_idx = 0;
-    let end_idx = grid.cells.len() - 1;
+    let samples: Vec<f64> = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            std::hint::black_box(find_path(grid, false, false, model, None));
+            start.elapsed().as_secs_f64() * 1e6
+        })
+        .collect();
+
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    let min = samples.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = samples.iter().copied().fold(0.0, f64::max);
+    let stddev = (samples.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / n).sqrt();
+
+    println!("BENCHMARK:");
+    println!("==========");
+    println!("Grid size: {}x{}", grid.width, grid.height);
+    println!("Algorithm: Dijkstra (minimum cost, {} cost model)", model.name());
+    println!("Runs: {} (+1 warm-up)", runs);
+    println!("Mean: {:.1} µs", mean);
+    println!("Min: {:.1} µs", min);
+    println!("Max: {:.1} µs", max);
+    println!("Std dev: {:.1} µs", stddev);
+}
+
+/// Copie du rectangle (x1,y1)-(x2,y2) inclus : (x1,y1) devient le départ, (x2,y2) l'arrivée
+fn extract_subgrid(grid: &Grid, x1: usize, y1: usize, x2: usize, y2: usize) -> Grid {
+    let indices: Vec<usize> = (y1..=y2).flat_map(|y| (x1..=x2).map(move |x| grid.get_index(x, y))).collect();
+
+    let mut sub = Grid::new(x2 - x1 + 1, y2 - y1 + 1, indices.iter().map(|&i| grid.cells[i]).collect());
+    sub.colors = grid.colors.as_ref().map(|colors| indices.iter().map(|&i| colors[i]).collect());
+    sub.wrap = grid.wrap;
+    sub.obstacles = grid.obstacles;
+    sub.min_cell = grid.min_cell;
+    sub.max_cell = grid.max_cell;
+    sub
+}
+
+/// Plus petit --max-cell-cost (les autres contraintes gardées) pour lequel un chemin existe.
+/// Monter le seuil ne fait qu'ouvrir des cellules : la recherche dichotomique est valide.
+fn find_min_threshold(grid: &Grid) -> Option<u8> {
+    let mut probe = grid.clone();
+    let mut has_path = |max_cell: u8| {
+        probe.max_cell = max_cell;
+        find_path(&probe, false, false, CostModel::Node, None).0.is_some()
+    };
+    if !has_path(255) {
+        return None;
+    }
+    let (mut lo, mut hi) = (0u8, 255u8);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if has_path(mid) { hi = mid; } else { lo = mid + 1; }
+    }
+    Some(lo)
+}
+
+/// Symétrique de find_min_threshold : plus grand --min-cell-cost laissant un chemin
+fn find_max_floor(grid: &Grid) -> Option<u8> {
+    let mut probe = grid.clone();
+    let mut has_path = |min_cell: u8| {
+        probe.min_cell = min_cell;
+        find_path(&probe, false, false, CostModel::Node, None).0.is_some()
+    };
+    if !has_path(0) {
+        return None;
+    }
+    let (mut lo, mut hi) = (0u8, 255u8);
+    while lo < hi {
+        let mid = lo + (hi - lo).div_ceil(2);
+        if has_path(mid) { lo = mid; } else { hi = mid - 1; }
+    }
+    Some(lo)
+}
+
+/// Étire les valeurs sur 00..FF : min -> 0x00, max -> 0xFF.
+/// Renvoie false (cellules inchangées) si toutes les valeurs sont égales.
+fn normalize_cells(cells: &mut [u8]) -> bool {
+    let (min, max, _) = cell_stats(cells);
+    if min == max {
+        return false;
+    }
+    let (min, range) = (min as u32, (max - min) as u32);
+    for cell in cells.iter_mut() {
+        *cell = ((*cell as u32 - min) * 255 / range) as u8;
+    }
+    true
+}
+
+/// cell = 255 - cell : le chemin minimum devient le chemin maximum de la grille d'origine
+fn invert_cells(cells: &mut [u8]) {
+    for cell in cells.iter_mut() {
+        *cell = 255 - *cell;
+    }
+}
+
+/// (min, max, moyenne) des valeurs de cellules
+fn cell_stats(cells: &[u8]) -> (u8, u8, f64) {
+    let min = cells.iter().copied().min().unwrap_or(0);
+    let max = cells.iter().copied().max().unwrap_or(0);
+    let mean = cells.iter().map(|&c| c as f64).sum::<f64>() / cells.len().max(1) as f64;
+    (min, max, mean)
+}
+
+fn print_cell_stats(label: &str, (min, max, mean): (u8, u8, f64)) {
+    println!("{} values: min 0x{:02X}, max 0x{:02X}, mean {:.1}", label, min, max, mean);
+}
+
+// ==========================================
+// ALGORITHME DIJKSTRA
+// ==========================================
+
+fn find_path(
+    grid: &Grid,
+    maximize: bool,
+    animate: bool,
+    model: CostModel,
+    frames_dir: Option<&Path>,
+) -> (Option<Vec<usize>>, u32, SearchStats) {
+    let start_idx = 0;
+    let end_idx = grid.cells.len() - 1;
 
     let mut dist = vec![u32::MAX; grid.cells.len()];
     let mut heap = BinaryHeap::new();
     let mut parents: HashMap<usize, usize> = HashMap::new();
 
+    let mut stats = SearchStats::default();
+
     dist[start_idx] = 0;
     heap.push(State { cost: 0, x: 0, y: 0 });
+    stats.enqueued += 1;
 
     let directions = [(0, 1), (1, 0), (0, -1), (-1, 0)];
     
     let mut steps_count = 0;
+    let mut frame_num = 0;
 
     while let Some(State { cost, x, y }) = heap.pop() {
         let current_idx = grid.get_index(x, y);
+        stats.popped += 1;
 
         if current_idx == end_idx {
             // Reconstruct path
@@ -263,10 +1370,11 @@ fn find_path(grid: &Grid, maximize: bool, animate: bool) -> (Option<Vec<usize>>,
                 path.push(curr);
             }
             path.reverse();
-            return (Some(path), cost);
+            return (Some(path), cost, stats);
         }
 
         if cost > dist[current_idx] {
+            stats.skipped += 1;
             continue;
         }
 
@@ -281,17 +1389,35 @@ fn find_path(grid: &Grid, maximize: bool, animate: bool) -> (Option<Vec<usize>>,
             steps_count += 1;
         }
 
+        // Une frame par état exploré
+        if let Some(dir) = frames_dir {
+            if let Err(e) = save_frame(dir, frame_num, grid, x, y, &parents) {
+                eprintln!("Error writing frame: {}", e);
+                process::exit(1);
+            }
+            frame_num += 1;
+        }
+
         for (dx, dy) in directions {
-            let new_x = x as isize + dx;
-            let new_y = y as isize + dy;
+            let mut new_x = x as isize + dx;
+            let mut new_y = y as isize + dy;
+
+            // --wrap : on ressort par le bord opposé
+            if grid.wrap {
+                new_x = new_x.rem_euclid(grid.width as isize);
+                new_y = new_y.rem_euclid(grid.height as isize);
+            }
 
             if new_x >= 0 && new_x < grid.width as isize && new_y >= 0 && new_y < grid.height as isize {
                 let nx = new_x as usize;
                 let ny = new_y as usize;
                 let next_idx = grid.get_index(nx, ny);
+                if !grid.is_passable(next_idx) {
+                    continue;
+                }
                 
-                let cell_val = grid.get_val(nx, ny) as u32;
-                let move_cost = if maximize { 255 - cell_val } else { cell_val };
+                let step_cost = compute_move_cost(model, grid.get_val(x, y), grid.get_val(nx, ny));
+                let move_cost = if maximize { 255 - step_cost } else { step_cost };
                 
                 let next_cost = cost + move_cost;
 
@@ -299,12 +1425,241 @@ fn find_path(grid: &Grid, maximize: bool, animate: bool) -> (Option<Vec<usize>>,
                     dist[next_idx] = next_cost;
                     parents.insert(next_idx, current_idx);
                     heap.push(State { cost: next_cost, x: nx, y: ny });
+                    stats.relaxed += 1;
+                    stats.enqueued += 1;
                 }
             }
         }
     }
 
-    (None, 0)
+    (None, 0, stats)
+}
+
+/// Distances minimales depuis (0,0) vers toutes les cellules (Dijkstra sans arrêt anticipé)
+fn shortest_distances(grid: &Grid, model: CostModel) -> Vec<u32> {
+    let mut dist = vec![u32::MAX; grid.cells.len()];
+    let mut heap = BinaryHeap::new();
+    dist[0] = 0;
+    heap.push(State { cost: 0, x: 0, y: 0 });
+
+    while let Some(State { cost, x, y }) = heap.pop() {
+        let idx = grid.get_index(x, y);
+        if cost > dist[idx] {
+            continue;
+        }
+        for next in neighbors(grid, idx) {
+            let next_cost = cost + compute_move_cost(model, grid.cells[idx], grid.cells[next]);
+            if next_cost < dist[next] {
+                dist[next] = next_cost;
+                let (nx, ny) = grid.get_xy(next);
+                heap.push(State { cost: next_cost, x: nx, y: ny });
+            }
+        }
+    }
+    dist
+}
+
+/// Dijkstra multi-source : toutes les cellules de départ entrent dans le tas à coût 0.
+/// dist[i] = coût depuis le départ le plus proche (u32::MAX si inatteignable) ;
+/// parents remonte de chaque cellule atteinte vers ce départ.
+fn find_path_multi_source(grid: &Grid, starts: &[(usize, usize)], model: CostModel) -> (Vec<u32>, HashMap<usize, usize>) {
+    let mut dist = vec![u32::MAX; grid.cells.len()];
+    let mut heap = BinaryHeap::new();
+    let mut parents: HashMap<usize, usize> = HashMap::new();
+
+    for &(x, y) in starts {
+        let idx = grid.get_index(x, y);
+        if dist[idx] != 0 {
+            dist[idx] = 0;
+            heap.push(State { cost: 0, x, y });
+        }
+    }
+
+    while let Some(State { cost, x, y }) = heap.pop() {
+        let idx = grid.get_index(x, y);
+        if cost > dist[idx] {
+            continue;
+        }
+        for next in neighbors(grid, idx) {
+            let next_cost = cost + compute_move_cost(model, grid.cells[idx], grid.cells[next]);
+            if next_cost < dist[next] {
+                dist[next] = next_cost;
+                parents.insert(next, idx);
+                let (nx, ny) = grid.get_xy(next);
+                heap.push(State { cost: next_cost, x: nx, y: ny });
+            }
+        }
+    }
+    (dist, parents)
+}
+
+/// Cellule la plus éloignée de tous les départs (recherche multi-source), puis centre :
+/// la cellule qui minimise le plus grand coût depuis chaque départ. Le centre demande
+/// une recherche par départ, le multi-source ne donnant que le départ le plus proche.
+fn run_multi_start(grid: &Grid, starts: &[(usize, usize)], model: CostModel) {
+    println!("\nMULTI-SOURCE SEARCH:");
+    println!("====================");
+    let labels: Vec<String> = starts.iter().map(|(x, y)| format!("({},{})", x, y)).collect();
+    println!("Start cells: {}", labels.join(" "));
+    println!("Cost model: {}", model.name());
+
+    let (dist, parents) = find_path_multi_source(grid, starts, model);
+    let reachable = || (0..grid.cells.len()).filter(|&i| dist[i] != u32::MAX);
+    if let Some(far) = reachable().max_by_key(|&i| (dist[i], std::cmp::Reverse(i))) {
+        let mut nearest = far;
+        while let Some(&p) = parents.get(&nearest) {
+            nearest = p;
+        }
+        let (fx, fy) = grid.get_xy(far);
+        let (nx, ny) = grid.get_xy(nearest);
+        println!("Farthest from all starts: ({},{}), cost {} from the nearest start ({},{})", fx, fy, dist[far], nx, ny);
+    }
+    let unreachable = grid.cells.len() - reachable().count();
+    if unreachable > 0 {
+        println!("Unreachable cells: {}", unreachable);
+    }
+
+    let per_start: Vec<Vec<u32>> = starts.iter().map(|&s| find_path_multi_source(grid, &[s], model).0).collect();
+    let center = (0..grid.cells.len())
+        .filter_map(|i| {
+            let worst = per_start.iter().map(|d| d[i]).max()?;
+            (worst != u32::MAX).then_some((worst, i))
+        })
+        .min();
+    match center {
+        Some((worst, idx)) => {
+            let (cx, cy) = grid.get_xy(idx);
+            println!("Center (minimax cost from the starts): ({},{}), max cost {}", cx, cy, worst);
+            for (label, d) in labels.iter().zip(&per_start) {
+                println!("  from {}: {}", label, d[idx]);
+            }
+        }
+        None => println!("No cell is reachable from every start"),
+    }
+}
+
+/// Nombre de chemins de coût minimal jusqu'à la dernière cellule :
+/// count[v] = somme des count[u] tels que dist[u] + coût(u, v) == dist[v] (pas « tendus »),
+/// en parcourant dans l'ordre topologique les cellules d'où l'arrivée reste atteignable.
+/// None si ces pas tendus forment un cycle (pas gratuits, ex. deux cellules 00 voisines) :
+/// on peut y tourner sans changer le coût, le nombre est illimité.
+/// Sature à u128::MAX en cas de dépassement.
+fn count_shortest_paths(grid: &Grid, dist: &[u32], model: CostModel) -> Option<u128> {
+    let n = grid.cells.len();
+    let end_idx = n - 1;
+    let tight = |u: usize, v: usize| {
+        dist[u] != u32::MAX && dist[u] + compute_move_cost(model, grid.cells[u], grid.cells[v]) == dist[v]
+    };
+
+    // Cellules qui mènent à l'arrivée par des pas tendus
+    let mut useful = vec![false; n];
+    useful[end_idx] = true;
+    let mut stack = vec![end_idx];
+    while let Some(v) = stack.pop() {
+        for u in neighbors(grid, v) {
+            if !useful[u] && tight(u, v) {
+                useful[u] = true;
+                stack.push(u);
+            }
+        }
+    }
+
+    // Kahn : une cellule est traitée quand tous ses prédécesseurs tendus le sont
+    let mut indegree = vec![0usize; n];
+    for v in (0..n).filter(|&v| useful[v]) {
+        indegree[v] = neighbors(grid, v).into_iter().filter(|&u| useful[u] && tight(u, v)).count();
+    }
+    let mut count = vec![0u128; n];
+    count[0] = 1;
+    let mut ready: Vec<usize> = (0..n).filter(|&v| useful[v] && indegree[v] == 0).collect();
+    let mut processed = 0;
+    while let Some(u) = ready.pop() {
+        processed += 1;
+        for v in neighbors(grid, u) {
+            if useful[v] && tight(u, v) {
+                count[v] = count[v].saturating_add(count[u]);
+                indegree[v] -= 1;
+                if indegree[v] == 0 {
+                    ready.push(v);
+                }
+            }
+        }
+    }
+
+    // Des cellules jamais libérées sont sur un cycle de pas gratuits
+    if processed < useful.iter().filter(|&&u| u).count() {
+        return None;
+    }
+    Some(count[end_idx])
+}
+
+// ==========================================
+// ALGORITHME BELLMAN-FORD
+// ==========================================
+
+// O(V * E) : au-delà, la comparaison devient beaucoup trop lente
+const BELLMAN_FORD_MAX_SIDE: usize = 100;
+
+/// Bellman-Ford puis vérification contre Dijkstra (panique en debug si les coûts diffèrent)
+fn run_bellman_ford(grid: &Grid, maximize: bool, model: CostModel) -> (Option<Vec<usize>>, u32) {
+    let (path, cost, passes) = find_path_bellman_ford(grid, maximize, model);
+    println!("Algorithm: Bellman-Ford ({} relaxation passes)", passes);
+
+    if cfg!(debug_assertions) {
+        let (dijkstra_path, dijkstra_cost, _) = find_path(grid, maximize, false, model, None);
+        assert_eq!(path.is_some(), dijkstra_path.is_some(), "Bellman-Ford and Dijkstra disagree on reachability");
+        assert_eq!(cost, dijkstra_cost, "Bellman-Ford and Dijkstra costs differ");
+    }
+    (path, cost)
+}
+
+/// Relâche toutes les arêtes au plus |V|-1 fois (arrêt anticipé si rien ne change).
+/// Renvoie aussi le nombre de passes effectuées.
+fn find_path_bellman_ford(grid: &Grid, maximize: bool, model: CostModel) -> (Option<Vec<usize>>, u32, usize) {
+    let n = grid.cells.len();
+    let end_idx = n - 1;
+
+    let mut dist = vec![u32::MAX; n];
+    let mut parents = vec![usize::MAX; n];
+    dist[0] = 0;
+
+    let mut passes = 0;
+    for _ in 1..n {
+        passes += 1;
+        let mut changed = false;
+
+        for idx in 0..n {
+            if dist[idx] == u32::MAX {
+                continue;
+            }
+            for next in neighbors(grid, idx) {
+                let step_cost = compute_move_cost(model, grid.cells[idx], grid.cells[next]);
+                let move_cost = if maximize { 255 - step_cost } else { step_cost };
+                if dist[idx] + move_cost < dist[next] {
+                    dist[next] = dist[idx] + move_cost;
+                    parents[next] = idx;
+                    changed = true;
+                }
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    if dist[end_idx] == u32::MAX {
+        return (None, 0, passes);
+    }
+
+    let mut path = vec![end_idx];
+    let mut curr = end_idx;
+    while curr != 0 {
+        curr = parents[curr];
+        path.push(curr);
+    }
+    path.reverse();
+    (Some(path), dist[end_idx], passes)
 }
 
 // ==========================================
@@ -320,18 +1675,17 @@ fn print_grid_values(cells: &[u8], width: usize) {
     println!();
 }
 
-fn print_path_stats(path: &[usize], _algo_cost: u32, grid: &Grid) {
-    let mut total_real: u32 = 0;
-    
+fn print_path_stats(path: &[usize], _algo_cost: u32, grid: &Grid, model: CostModel) {
+    // Coût de chaque pas selon le modèle choisi
+    let step_cost = |i: usize| compute_move_cost(model, grid.cells[path[i - 1]], grid.cells[path[i]]);
+
     // Calcul du vrai coût pour affichage
-    for (i, &idx) in path.iter().enumerate() {
-        let (x, y) = grid.get_xy(idx);
-        let val = grid.get_val(x, y);
-        if i > 0 { 
-             total_real += val as u32;
-        }
-    }
+    let total_real: u32 = (1..path.len()).map(step_cost).sum();
     
+    println!("Cost model: {}", model.name());
+    if grid.wrap {
+        println!("[WRAP] Toroidal grid: moves may cross an edge to the opposite side");
+    }
     println!("Total cost: 0x{:X} ({} decimal)", total_real, total_real);
     println!("Path length: {} steps", path.len()); 
     
@@ -351,7 +1705,7 @@ fn print_path_stats(path: &[usize], _algo_cost: u32, grid: &Grid) {
             if i == 0 {
                 println!("Start 0x{:02X} (0,0)", val);
             } else {
-                running_cost += val as u32;
+                running_cost += step_cost(i);
                 println!("-> 0x{:02X} ({},{}) +{}", val, x, y, running_cost);
             }
         }
@@ -359,50 +1713,650 @@ fn print_path_stats(path: &[usize], _algo_cost: u32, grid: &Grid) {
     }
 }
 
-fn print_colored_grid(grid: &Grid, path: &[usize]) {
+/// Bornes d'un chemin monotone (width + height - 2 pas) : (min(cells), max(cells)) par pas.
+/// En --wrap, le coin opposé est à un pas par axe en passant par les bords.
+fn path_bounds(grid: &Grid) -> (u32, u32) {
+    let steps = if grid.wrap {
+        (grid.width - 1).min(1) + (grid.height - 1).min(1)
+    } else {
+        grid.width + grid.height - 2
+    } as u32;
+    let min = grid.cells.iter().copied().min().unwrap_or(0) as u32;
+    let max = grid.cells.iter().copied().max().unwrap_or(0) as u32;
+    (steps * min, steps * max)
+}
+
+fn print_bounds(actual_cost: u32, grid: &Grid) {
+    let (lower, upper) = path_bounds(grid);
+    println!("\nTheoretical lower bound: {}", lower);
+    println!("Theoretical upper bound: {}", upper);
+    if lower > 0 {
+        println!("Efficiency: {:.2}", actual_cost as f64 / lower as f64);
+    } else {
+        println!("Efficiency: n/a (lower bound is 0)");
+    }
+    if actual_cost == lower {
+        println!("\x1b[32m✓ Optimal path found!\x1b[0m");
+    }
+}
+
+fn print_search_stats(stats: &SearchStats) {
+    println!("\nSEARCH PROFILE:");
+    println!("===============");
+    println!("Nodes enqueued: {}", stats.enqueued);
+    println!("Nodes popped: {}", stats.popped);
+    println!("Nodes skipped (stale): {}", stats.skipped);
+    println!("Nodes relaxed: {}", stats.relaxed);
+}
+
+/// Ajoute une ligne au CSV, avec l'en-tête si le fichier est vide ou nouveau
+fn append_profile_csv(path: &Path, grid: &Grid, stats: &SearchStats, path_cost: Option<u32>) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new().append(true).create(true).open(path)?;
+    if file.metadata()?.len() == 0 {
+        writeln!(file, "grid_w,grid_h,algo,enqueued,popped,skipped,relaxed,path_cost")?;
+    }
+    let cost = path_cost.map(|c| c.to_string()).unwrap_or_default();
+    writeln!(
+        file,
+        "{},{},dijkstra,{},{},{},{},{}",
+        grid.width, grid.height, stats.enqueued, stats.popped, stats.skipped, stats.relaxed, cost
+    )
+}
+
+fn print_grid(grid: &Grid, path: &[usize], scheme: ColorScheme, annotate: bool) {
+    if annotate {
+        print_annotated_grid(grid, path, scheme);
+    } else {
+        print_colored_grid(grid, path, scheme);
+    }
+}
+
+fn print_colored_grid(grid: &Grid, path: &[usize], scheme: ColorScheme) {
     for y in 0..grid.height {
-        for x in 0..grid.width {
-            let idx = grid.get_index(x, y);
-            let val = grid.cells[idx];
-            let is_path = path.contains(&idx);
+        print_grid_row(grid, y, path, scheme);
+        println!();
+    }
+    print_wrap_note(grid);
+}
+
+/// Cellules de la ligne `y`, sans retour à la ligne (chaque cellule finit par un reset ANSI)
+fn print_grid_row(grid: &Grid, y: usize, path: &[usize], scheme: ColorScheme) {
+    for x in 0..grid.width {
+        let idx = grid.get_index(x, y);
+        let val = grid.cells[idx];
+        let is_path = path.contains(&idx);
+
+        // 3 caractères comme les autres cellules, sinon les en-têtes de --annotate décalent
+        if is_path {
+            print!("\x1b[48;2;255;255;255m\x1b[38;2;0;0;0m{:02X}\x1b[0m ", val);
+        } else {
+            let (r, g, b) = match &grid.colors {
+                Some(colors) => colors[idx],
+                None => scheme.apply(val),
+            };
+            print!("\x1b[38;2;{};{};{}m{:02X} \x1b[0m", r, g, b, val);
+        }
+    }
+}
 
-            if is_path {
-                print!("\x1b[48;2;255;255;255m\x1b[38;2;0;0;0m {:02X} \x1b[0m", val);
+fn print_wrap_note(grid: &Grid) {
+    if grid.wrap {
+        println!("[WRAP] Toroidal grid: opposite edges are connected");
+    }
+}
+
+// Au-delà, les numéros à 3 chiffres ne tiennent plus dans une cellule : un sur 5
+const ANNOTATE_FULL_MAX: usize = 100;
+
+fn label_step(len: usize) -> usize {
+    if len <= ANNOTATE_FULL_MAX { 1 } else { 5 }
+}
+
+/// print_colored_grid avec numéros de colonne en haut et de ligne à gauche.
+/// Les libellés sont précédés d'un reset ANSI pour ne pas hériter d'une couleur.
+fn print_annotated_grid(grid: &Grid, path: &[usize], scheme: ColorScheme) {
+    let (col_step, row_step) = (label_step(grid.width), label_step(grid.height));
+    let margin = (grid.height - 1).to_string().len() + 1;
+
+    // Une cellule fait 3 caractères : chaque numéro occupe les cellules jusqu'au suivant
+    let header: String = (0..grid.width)
+        .step_by(col_step)
+        .map(|x| format!("{:<w$}", x, w = 3 * col_step))
+        .collect();
+    println!("\x1b[0m{:margin$}{}", "", header.trim_end(), margin = margin);
+
+    for y in 0..grid.height {
+        let label = if y.is_multiple_of(row_step) { y.to_string() } else { String::new() };
+        print!("\x1b[0m{:>w$} ", label, w = margin - 1);
+        print_grid_row(grid, y, path, scheme);
+        println!();
+    }
+    print_wrap_note(grid);
+}
+
+// Longueur maximale (en pas) d'une portion remplacée par un L : borne le coût du lissage
+const SMOOTH_MAX_SPAN: usize = 64;
+
+/// Lissage du chemin selon le modèle de coût, répété jusqu'à ce que plus rien ne change :
+/// - pour chaque triplet (A, B, C), B est retiré si A et C sont voisins (seul --wrap
+///   le permet) et que A -> C ne coûte pas plus que A -> B -> C ;
+/// - une portion en escalier (jusqu'à SMOOTH_MAX_SPAN pas) est remplacée par un L ou
+///   une ligne droite qui ne coûte pas plus et compte moins de virages.
+fn smooth_path(path: &[usize], grid: &Grid, model: CostModel) -> Vec<usize> {
+    let step_cost = |from: usize, to: usize| compute_move_cost(model, grid.cells[from], grid.cells[to]);
+    let mut smoothed = path.to_vec();
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        let mut i = 0;
+        while i + 2 < smoothed.len() {
+            let (a, b, c) = (smoothed[i], smoothed[i + 1], smoothed[i + 2]);
+            if neighbors(grid, a).contains(&c) && step_cost(a, c) <= step_cost(a, b) + step_cost(b, c) {
+                smoothed.remove(i + 1);
+                changed = true;
+                // Le triplet précédent a changé : on le réexamine
+                i = i.saturating_sub(1);
             } else {
-                let (r, g, b) = hex_to_rgb(val);
-                print!("\x1b[38;2;{};{};{}m{:02X} \x1b[0m", r, g, b, val);
+                i += 1;
+            }
+        }
+
+        // position[cellule] = index dans le chemin, pour refuser un L qui le recroiserait
+        let mut position = vec![usize::MAX; grid.cells.len()];
+        for (k, &cell) in smoothed.iter().enumerate() {
+            position[cell] = k;
+        }
+        for i in 0..smoothed.len() {
+            if let Some((j, route)) = straighter_route(grid, &smoothed, &position, i, &step_cost) {
+                smoothed.iter().for_each(|&cell| position[cell] = usize::MAX);
+                smoothed.splice(i + 1..j, route);
+                smoothed.iter().enumerate().for_each(|(k, &cell)| position[cell] = k);
+                changed = true;
+            }
+        }
+    }
+    smoothed
+}
+
+/// Plus longue portion path[i..=j] remplaçable par un L de path[i] à path[j] qui ne coûte
+/// pas plus et enlève des virages. Renvoie j et les cellules intermédiaires du L.
+fn straighter_route(
+    grid: &Grid,
+    path: &[usize],
+    position: &[usize],
+    i: usize,
+    step_cost: &impl Fn(usize, usize) -> u32,
+) -> Option<(usize, Vec<usize>)> {
+    let cost = |cells: &[usize]| cells.windows(2).map(|w| step_cost(w[0], w[1])).sum::<u32>();
+    let last = (i + SMOOTH_MAX_SPAN).min(path.len().checked_sub(1)?);
+    // Virages comptés avec le pas précédent et le suivant
+    let before = i.saturating_sub(1);
+
+    for j in (i + 2..=last).rev() {
+        let after = (j + 1).min(path.len() - 1);
+        for horizontal_first in [true, false] {
+            let Some(route) = l_route(grid, path[i], path[j], horizontal_first) else {
+                continue;
+            };
+            if route.iter().any(|&cell| position[cell] != usize::MAX && (position[cell] <= i || position[cell] >= j)) {
+                continue;
+            }
+            let candidate: Vec<usize> = path[before..=i]
+                .iter()
+                .chain(&route)
+                .chain(&path[j..=after])
+                .copied()
+                .collect();
+            let offset = i - before;
+            let replaced = &candidate[offset..candidate.len() - (after - j)];
+            if cost(replaced) <= cost(&path[i..=j])
+                && count_turns(grid, &candidate) < count_turns(grid, &path[before..=after])
+            {
+                return Some((j, route));
             }
         }
-        println!();
     }
+    None
+}
+
+/// Cellules strictement entre `from` et `to` sur le L qui suit d'abord l'axe horizontal
+/// (ou vertical), sans passer par les bords en --wrap. None si l'une est impraticable.
+fn l_route(grid: &Grid, from: usize, to: usize, horizontal_first: bool) -> Option<Vec<usize>> {
+    let ((fx, fy), (tx, ty)) = (grid.get_xy(from), grid.get_xy(to));
+    let (sx, sy) = ((tx as isize - fx as isize).signum(), (ty as isize - fy as isize).signum());
+    let legs = if horizontal_first { [(sx, 0), (0, sy)] } else { [(0, sy), (sx, 0)] };
+
+    let mut cells = Vec::new();
+    let (mut x, mut y) = (fx, fy);
+    for (dx, dy) in legs {
+        while (dx != 0 && x != tx) || (dy != 0 && y != ty) {
+            x = (x as isize + dx) as usize;
+            y = (y as isize + dy) as usize;
+            let idx = grid.get_index(x, y);
+            if idx != to && !grid.is_passable(idx) {
+                return None;
+            }
+            cells.push(idx);
+        }
+    }
+    cells.pop();
+    Some(cells)
+}
+
+/// Direction (dx, dy) du pas from -> to, None si les cellules ne sont pas voisines
+fn direction(grid: &Grid, from: usize, to: usize) -> Option<(isize, isize)> {
+    DIRECTIONS.into_iter().find(|&d| step(grid, from, d) == Some(to))
+}
+
+/// Nombre de changements de direction le long du chemin
+fn count_turns(grid: &Grid, path: &[usize]) -> usize {
+    path.windows(3)
+        .filter(|w| direction(grid, w[0], w[1]) != direction(grid, w[1], w[2]))
+        .count()
+}
+
+// ==========================================
+// MODE INTERACTIF (crossterm)
+// ==========================================
+
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Marche manuelle de (0,0) vers la dernière cellule, coût cumulé selon le modèle
+struct InteractiveWalker<'a> {
+    grid: &'a Grid,
+    model: CostModel,
+    pos: usize,
+    cost: u32,
+    /// Cellules parcourues, dans l'ordre
+    trail: Vec<usize>,
+    visited: Vec<bool>,
+    allow_revisit: bool,
+    status: String,
+}
+
+impl<'a> InteractiveWalker<'a> {
+    fn new(grid: &'a Grid, model: CostModel, allow_revisit: bool) -> Self {
+        let mut visited = vec![false; grid.cells.len()];
+        visited[0] = true;
+        InteractiveWalker {
+            grid,
+            model,
+            pos: 0,
+            cost: 0,
+            trail: vec![0],
+            visited,
+            allow_revisit,
+            status: "arrows: move  q: quit".to_string(),
+        }
+    }
+
+    /// Avance d'une cellule si le déplacement est permis, retourne true à l'arrivée
+    fn step(&mut self, dir: Direction) -> bool {
+        let (x, y) = self.grid.get_xy(self.pos);
+        let (dx, dy) = match dir {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        };
+        let mut nx = x as isize + dx;
+        let mut ny = y as isize + dy;
+        if self.grid.wrap {
+            nx = nx.rem_euclid(self.grid.width as isize);
+            ny = ny.rem_euclid(self.grid.height as isize);
+        }
+        if nx < 0 || nx >= self.grid.width as isize || ny < 0 || ny >= self.grid.height as isize {
+            self.status = "edge of the grid".to_string();
+            return false;
+        }
+
+        let next = self.grid.get_index(nx as usize, ny as usize);
+        if !self.grid.is_passable(next) {
+            self.status = format!("({},{}) is a wall", nx, ny);
+            return false;
+        }
+        if self.visited[next] && !self.allow_revisit {
+            self.status = format!("({},{}) already visited (use --allow-revisit)", nx, ny);
+            return false;
+        }
+
+        let step_cost = compute_move_cost(self.model, self.grid.cells[self.pos], self.grid.cells[next]);
+        self.cost += step_cost;
+        self.pos = next;
+        self.visited[next] = true;
+        self.trail.push(next);
+        self.status = format!("({},{}) +{}", nx, ny, step_cost);
+        self.pos == self.grid.cells.len() - 1
+    }
+
+    /// Fenêtre (colonne, ligne) de départ qui garde la position courante visible
+    fn viewport(&self, cols: usize, rows: usize) -> (usize, usize) {
+        let (x, y) = self.grid.get_xy(self.pos);
+        let left = x.saturating_sub(cols / 2).min(self.grid.width.saturating_sub(cols));
+        let top = y.saturating_sub(rows / 2).min(self.grid.height.saturating_sub(rows));
+        (left, top)
+    }
+
+    /// Position courante en vidéo inverse, chemin parcouru souligné, chemin optimal estompé
+    fn render(&self, out: &mut impl Write, optimal: &[usize], cols: usize, rows: usize) -> io::Result<()> {
+        queue!(out, MoveTo(0, 0), Clear(ClearType::All))?;
+        let (left, top) = self.viewport(cols, rows);
+
+        for y in top..(top + rows).min(self.grid.height) {
+            queue!(out, MoveTo(0, (y - top) as u16))?;
+            for x in left..(left + cols).min(self.grid.width) {
+                let idx = self.grid.get_index(x, y);
+                let attr = if idx == self.pos {
+                    Attribute::Reverse
+                } else if self.visited[idx] {
+                    Attribute::Underlined
+                } else if optimal.contains(&idx) {
+                    Attribute::Dim
+                } else {
+                    Attribute::Reset
+                };
+                queue!(
+                    out,
+                    SetAttribute(attr),
+                    Print(format!("{:02X}", self.grid.cells[idx])),
+                    SetAttribute(Attribute::Reset),
+                    Print(" ")
+                )?;
+            }
+        }
+
+        let (x, y) = self.grid.get_xy(self.pos);
+        queue!(
+            out,
+            MoveTo(0, rows as u16),
+            Print(format!("Position ({},{}) - cost {} - {} steps", x, y, self.cost, self.trail.len() - 1)),
+            MoveTo(0, rows as u16 + 1),
+            Print(&self.status),
+        )?;
+        out.flush()
+    }
+}
+
+/// Marche plein écran puis comparaison avec le coût optimal de Dijkstra
+fn run_interactive(grid: &Grid, model: CostModel, allow_revisit: bool) -> io::Result<()> {
+    let (optimal_path, optimal_cost, _) = find_path(grid, false, false, model, None);
+    let optimal = optimal_path.unwrap_or_default();
+    let mut walker = InteractiveWalker::new(grid, model, allow_revisit);
+
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(stdout, EnterAlternateScreen, Hide)?;
+
+    let result = (|| -> io::Result<bool> {
+        loop {
+            // Deux lignes réservées en bas pour l'état, 3 colonnes par cellule
+            let (width, height) = terminal::size()?;
+            let cols = (width as usize / 3).max(1);
+            let rows = (height as usize).saturating_sub(2).max(1);
+            walker.render(&mut stdout, &optimal, cols, rows)?;
+
+            let Event::Key(key) = event::read()? else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let dir = match key.code {
+                KeyCode::Up => Direction::Up,
+                KeyCode::Down => Direction::Down,
+                KeyCode::Left => Direction::Left,
+                KeyCode::Right => Direction::Right,
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+                _ => continue,
+            };
+            if walker.step(dir) {
+                return Ok(true);
+            }
+        }
+    })();
+
+    // On restaure toujours le terminal, même en cas d'erreur
+    execute!(stdout, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    if !result? {
+        println!("Walk abandoned after {} steps (cost so far: {})", walker.trail.len() - 1, walker.cost);
+        return Ok(());
+    }
+
+    println!("\nEND REACHED!");
+    println!("============");
+    println!("Your cost: {} ({} steps)", walker.cost, walker.trail.len() - 1);
+    println!("Optimal cost: {} ({} steps)", optimal_cost, optimal.len().saturating_sub(1));
+    if optimal_cost > 0 {
+        println!("Efficiency: {:.2} (1.00 = optimal)", walker.cost as f64 / optimal_cost as f64);
+    } else {
+        println!("Efficiency: n/a (optimal cost is 0)");
+    }
+    if walker.cost == optimal_cost {
+        println!("\x1b[32m✓ You found an optimal path!\x1b[0m");
+    }
+    Ok(())
+}
+
+// ==========================================
+// EXPORT GRAPHVIZ
+// ==========================================
+
+// Distance (en pas) autour du chemin conservée sans --dot-full
+const DOT_NEIGHBORHOOD: usize = 2;
+
+/// Voisins cardinaux d'une cellule (bords opposés inclus en --wrap)
+const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+/// Cellule praticable atteinte en un pas (dx, dy) depuis idx, bords opposés reliés en --wrap
+fn step(grid: &Grid, idx: usize, (dx, dy): (isize, isize)) -> Option<usize> {
+    let (x, y) = grid.get_xy(idx);
+    let mut nx = x as isize + dx;
+    let mut ny = y as isize + dy;
+    if grid.wrap {
+        nx = nx.rem_euclid(grid.width as isize);
+        ny = ny.rem_euclid(grid.height as isize);
+    }
+    if nx < 0 || nx >= grid.width as isize || ny < 0 || ny >= grid.height as isize {
+        return None;
+    }
+    let next = grid.get_index(nx as usize, ny as usize);
+    // Grilles de 1 de large en --wrap : boucle sur soi
+    (next != idx && grid.is_passable(next)).then_some(next)
+}
+
+fn neighbors(grid: &Grid, idx: usize) -> Vec<usize> {
+    let mut result = Vec::with_capacity(4);
+    for next in DIRECTIONS.into_iter().filter_map(|d| step(grid, idx, d)) {
+        // Grilles de 2 de large en --wrap : le même voisin des deux côtés
+        if !result.contains(&next) {
+            result.push(next);
+        }
+    }
+    result
+}
+
+/// Graphe orienté : un nœud par cellule, une arête vers chaque voisin pondérée
+/// par la valeur de la destination. Sans `full`, seules les cellules à
+/// DOT_NEIGHBORHOOD pas ou moins du chemin sont écrites.
+fn export_dot(grid: &Grid, path: &[usize], full: bool, out: &mut dyn Write) -> io::Result<()> {
+    let mut included = vec![full || path.is_empty(); grid.cells.len()];
+    let mut frontier: Vec<usize> = path.to_vec();
+    for &idx in path {
+        included[idx] = true;
+    }
+    for _ in 0..DOT_NEIGHBORHOOD {
+        let mut next = Vec::new();
+        for &idx in &frontier {
+            for n in neighbors(grid, idx) {
+                if !included[n] {
+                    included[n] = true;
+                    next.push(n);
+                }
+            }
+        }
+        frontier = next;
+    }
+
+    let path_edges: HashSet<(usize, usize)> = path.windows(2).map(|w| (w[0], w[1])).collect();
+
+    writeln!(out, "digraph grid {{")?;
+    writeln!(out, "    node [shape=box, fontname=\"monospace\"];")?;
+    for idx in (0..grid.cells.len()).filter(|&i| included[i]) {
+        let (x, y) = grid.get_xy(idx);
+        let color = if path.contains(&idx) { ", color=red" } else { "" };
+        writeln!(out, "    n{} [label=\"0x{:02X}\\n({},{})\"{}];", idx, grid.cells[idx], x, y, color)?;
+    }
+    for idx in (0..grid.cells.len()).filter(|&i| included[i]) {
+        for n in neighbors(grid, idx).into_iter().filter(|&n| included[n]) {
+            let weight = grid.cells[n];
+            let color = if path_edges.contains(&(idx, n)) { "red" } else { "gray" };
+            writeln!(out, "    n{} -> n{} [weight={}, label=\"{}\", color={}];", idx, n, weight, weight, color)?;
+        }
+    }
+    writeln!(out, "}}")
 }
 
 fn print_anim_grid(grid: &Grid, cur_x: usize, cur_y: usize, parents: &HashMap<usize, usize>) {
+    let _ = write_anim_grid(&mut io::stdout(), grid, cur_x, cur_y, parents);
+}
+
+fn write_anim_grid(
+    out: &mut impl Write,
+    grid: &Grid,
+    cur_x: usize,
+    cur_y: usize,
+    parents: &HashMap<usize, usize>,
+) -> io::Result<()> {
     for y in 0..grid.height {
         for x in 0..grid.width {
             let idx = grid.get_index(x, y);
             if x == cur_x && y == cur_y {
-                print!("[*]");
+                write!(out, "[*]")?;
             } else if parents.contains_key(&idx) || idx == 0 {
-                print!("[✓]");
+                write!(out, "[✓]")?;
             } else {
-                print!("[ ]");
+                write!(out, "[ ]")?;
             }
         }
-        println!();
+        writeln!(out)?;
     }
+    Ok(())
 }
 
-fn hex_to_rgb(val: u8) -> (u8, u8, u8) {
-    if val < 128 {
-        let ratio = val as f32 / 128.0;
-        let r = ((1.0 - ratio) * 255.0) as u8;
-        let g = (ratio * 255.0) as u8;
-        (r + 50, g + 50, 0)
-    } else {
-        let ratio = (val - 128) as f32 / 127.0;
-        let g = ((1.0 - ratio) * 255.0) as u8;
-        let b = (ratio * 255.0) as u8;
-        (0, g + 50, b + 50)
+/// Même rendu que --animate, dans <dir>/frame_<N>.txt (N sur 6 chiffres)
+fn save_frame(
+    dir: &Path,
+    frame_num: usize,
+    grid: &Grid,
+    cur_x: usize,
+    cur_y: usize,
+    parents: &HashMap<usize, usize>,
+) -> io::Result<()> {
+    let mut file = File::create(dir.join(format!("frame_{:06}.txt", frame_num)))?;
+    writeln!(file, "Frame {}: Exploring ({},{})\n", frame_num, cur_x, cur_y)?;
+    write_anim_grid(&mut file, grid, cur_x, cur_y, parents)
+}
+
+/// Interpolation linéaire entre des couleurs réparties uniformément sur 00..FF
+fn gradient(stops: &[(u8, u8, u8)], val: u8) -> (u8, u8, u8) {
+    let segments = (stops.len() - 1) as f32;
+    let pos = val as f32 / 255.0 * segments;
+    let i = (pos as usize).min(stops.len() - 2);
+    let t = pos - i as f32;
+
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    let (from, to) = (stops[i], stops[i + 1]);
+    (lerp(from.0, to.0), lerp(from.1, to.1), lerp(from.2, to.2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_generates_same_grid() {
+        let a = generate_grid(10, 10, 42, Distribution::Uniform);
+        let b = generate_grid(10, 10, 42, Distribution::Uniform);
+        assert_eq!(a.cells, b.cells);
+        assert_ne!(a.cells, generate_grid(10, 10, 43, Distribution::Uniform).cells);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn cost_models_agree_on_uniform_grid() {
+        let grid = Grid::new(6, 4, vec![0x20; 24]);
+        let costs: Vec<u32> = [CostModel::Node, CostModel::EdgeAvg, CostModel::EdgeMax]
+            .into_iter()
+            .map(|model| {
+                let (path, cost, _) = find_path(&grid, false, false, model, None);
+                assert!(path.is_some());
+                cost
+            })
+            .collect();
+        // 5 + 3 pas à 0x20
+        assert_eq!(costs, vec![8 * 0x20; 3]);
+    }
+
+    #[test]
+    fn smoothing_follows_cost_model() {
+        // 3x1 torique : (0,0) et (2,0) sont voisins par le bord
+        let mut grid = Grid::new(3, 1, vec![0x01, 0x00, 0x01]);
+        grid.wrap = true;
+        assert_eq!(smooth_path(&[0, 1, 2], &grid, CostModel::Node), vec![0, 2]);
+        // edge-avg : direct (1+1)/2 = 1, détour (1+0)/2 + (0+1)/2 = 0
+        assert_eq!(smooth_path(&[0, 1, 2], &grid, CostModel::EdgeAvg), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn generation_only_args_skip_analysis() {
+        let matches = |argv: &[&str]| Args::command().get_matches_from(argv);
+        assert!(!requests_analysis(&matches(&["hextool", "--generate", "5x5", "--seed", "1"]), &[]));
+        assert!(requests_analysis(&matches(&["hextool", "--generate", "5x5", "--count-paths"]), &[]));
+        assert!(requests_analysis(&matches(&["hextool", "--generate", "5x5"]), &["smooth"]));
+    }
+
+    #[test]
+    fn path_count_follows_zero_cost_moves() {
+        // Deux chemins 0 -> 1 -> 3 et 0 -> 2 -> 3, les 00 ne sont pas voisins
+        let grid = Grid::new(2, 2, vec![0x05, 0x00, 0x00, 0x05]);
+        assert_eq!(count_shortest_paths(&grid, &shortest_distances(&grid, CostModel::Node), CostModel::Node), Some(2));
+
+        // Serpentin : la ligne 2 se parcourt à rebours, 7 -> 6 est gratuit
+        let mut grid = Grid::new(3, 5, vec![
+            0x00, 0x01, 0x01,
+            0xFF, 0xFF, 0x01,
+            0x00, 0x01, 0x01,
+            0x01, 0xFF, 0xFF,
+            0x01, 0x01, 0x01,
+        ]);
+        grid.obstacles = true;
+        assert_eq!(count_shortest_paths(&grid, &shortest_distances(&grid, CostModel::Node), CostModel::Node), Some(1));
+
+        // 00 voisins : aller-retour gratuit, nombre illimité
+        let grid = Grid::new(3, 1, vec![0x00, 0x00, 0x00]);
+        assert_eq!(count_shortest_paths(&grid, &shortest_distances(&grid, CostModel::Node), CostModel::Node), None);
+    }
+
+    #[test]
+    fn smoothing_straightens_equal_cost_staircase() {
+        // Escalier R, D, R, D sur une grille uniforme : même coût en R, R, D, D
+        let grid = Grid::new(3, 3, vec![0x01; 9]);
+        let staircase = [0, 1, 4, 5, 8];
+        let smoothed = smooth_path(&staircase, &grid, CostModel::Node);
+        assert_eq!(smoothed.len(), staircase.len());
+        assert!(count_turns(&grid, &smoothed) < count_turns(&grid, &staircase));
+        // Marches de 2 pas : aucun coin seul n'aide, tout l'escalier devient un L
+        let grid = Grid::new(5, 5, vec![0x01; 25]);
+        let wide = [0, 1, 2, 7, 12, 13, 14, 19, 24];
+        assert_eq!(count_turns(&grid, &smooth_path(&wide, &grid, CostModel::Node)), 1);
+        // Autres coins (2, 3 et 7) plus chers : on garde l'escalier
+        let grid = Grid::new(3, 3, vec![0x01, 0x01, 0x09, 0x09, 0x01, 0x01, 0x01, 0x09, 0x01]);
+        assert_eq!(smooth_path(&staircase, &grid, CostModel::Node), staircase);
+    }
+}
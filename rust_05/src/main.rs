@@ -0,0 +1,305 @@
+use clap::Parser;
+use std::fs;
+use std::process;
+
+/// Compute the Levenshtein edit distance between two words
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// First word (the one transformed)
+    #[arg(long, required_unless_present = "batch", conflicts_with = "batch")]
+    word1: Option<String>,
+
+    /// Second word (the target)
+    #[arg(long, required_unless_present = "batch", conflicts_with = "batch")]
+    word2: Option<String>,
+
+    /// Print the edit operations turning word1 into word2
+    #[arg(long)]
+    path: bool,
+
+    /// Print the full DP table with row/column labels
+    #[arg(long)]
+    matrix: bool,
+
+    /// Compare Unicode characters instead of bytes
+    #[arg(long)]
+    unicode: bool,
+
+    /// CSV file of "word1,word2" pairs, one distance printed per pair
+    #[arg(long, conflicts_with_all = ["path", "matrix"])]
+    batch: Option<String>,
+}
+
+// ==========================================
+// UNITÉS COMPARÉES (octets ou caractères)
+// ==========================================
+
+/// Unité de comparaison : u8 par défaut, char avec --unicode
+trait Symbol: PartialEq + Copy {
+    /// Représentation affichable (octets non ASCII en \xNN)
+    fn label(&self) -> String;
+}
+
+impl Symbol for u8 {
+    fn label(&self) -> String {
+        if self.is_ascii_graphic() || *self == b' ' {
+            (*self as char).to_string()
+        } else {
+            format!("\\x{:02X}", self)
+        }
+    }
+}
+
+impl Symbol for char {
+    fn label(&self) -> String {
+        self.to_string()
+    }
+}
+
+// ==========================================
+// PROGRAMMATION DYNAMIQUE
+// ==========================================
+
+/// table[i][j] = distance entre les i premières unités de a et les j premières de b
+fn distance_table<T: Symbol>(a: &[T], b: &[T]) -> Vec<Vec<usize>> {
+    let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in table[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            table[i][j] = (table[i - 1][j] + 1)
+                .min(table[i][j - 1] + 1)
+                .min(table[i - 1][j - 1] + substitution);
+        }
+    }
+    table
+}
+
+/// Distance octet par octet (un caractère multi-octets compte pour plusieurs unités)
+fn levenshtein(a: &str, b: &str) -> usize {
+    distance_table(a.as_bytes(), b.as_bytes())[a.len()][b.len()]
+}
+
+/// Distance caractère par caractère (--unicode)
+fn levenshtein_chars(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    distance_table(&a, &b)[a.len()][b.len()]
+}
+
+#[derive(Debug)]
+enum EditOp<T> {
+    Insert { pos: usize, symbol: T },
+    Delete { pos: usize, symbol: T },
+    Substitute { pos: usize, from: T, to: T },
+}
+
+/// Remonte la table depuis le coin (a.len(), b.len()) : les positions sont celles
+/// du mot en cours de transformation, en appliquant les opérations dans l'ordre
+fn edit_path<T: Symbol>(a: &[T], b: &[T], table: &[Vec<usize>]) -> Vec<EditOp<T>> {
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (a.len(), b.len());
+
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && a[i - 1] == b[j - 1] && table[i][j] == table[i - 1][j - 1] {
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && j > 0 && table[i][j] == table[i - 1][j - 1] + 1 {
+            ops.push(EditOp::Substitute { pos: j - 1, from: a[i - 1], to: b[j - 1] });
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && table[i][j] == table[i - 1][j] + 1 {
+            ops.push(EditOp::Delete { pos: j, symbol: a[i - 1] });
+            i -= 1;
+        } else {
+            ops.push(EditOp::Insert { pos: j - 1, symbol: b[j - 1] });
+            j -= 1;
+        }
+    }
+
+    // En partant de la fin, chaque position ne dépend que du préfixe déjà transformé
+    // de b : on rend la liste dans l'ordre de lecture
+    ops.reverse();
+    ops
+}
+
+// ==========================================
+// AFFICHAGE
+// ==========================================
+
+fn print_path<T: Symbol>(ops: &[EditOp<T>]) {
+    println!("\nEdit path ({} operations):", ops.len());
+    if ops.is_empty() {
+        println!("  (words are identical)");
+    }
+    for (n, op) in ops.iter().enumerate() {
+        let text = match op {
+            EditOp::Insert { pos, symbol } => format!("Insert '{}' at {}", symbol.label(), pos),
+            EditOp::Delete { pos, symbol } => format!("Delete '{}' at {}", symbol.label(), pos),
+            EditOp::Substitute { pos, from, to } => {
+                format!("Substitute '{}' -> '{}' at {}", from.label(), to.label(), pos)
+            }
+        };
+        println!("  {}. {}", n + 1, text);
+    }
+}
+
+/// Lignes = unités de a, colonnes = unités de b, ε pour le préfixe vide
+fn print_matrix<T: Symbol>(a: &[T], b: &[T], table: &[Vec<usize>]) {
+    let width = table
+        .iter()
+        .flatten()
+        .map(|d| d.to_string().len())
+        .chain(a.iter().chain(b).map(|s| s.label().chars().count()))
+        .max()
+        .unwrap_or(1)
+        .max(1);
+
+    println!("\nDP matrix:");
+    let header: Vec<String> = std::iter::once("ε".to_string()).chain(b.iter().map(Symbol::label)).collect();
+    print!("{:>width$} ", "", width = width);
+    for label in &header {
+        print!(" {:>width$}", label, width = width);
+    }
+    println!();
+
+    for (i, row) in table.iter().enumerate() {
+        let label = if i == 0 { "ε".to_string() } else { a[i - 1].label() };
+        print!("{:>width$} ", label, width = width);
+        for d in row {
+            print!(" {:>width$}", d, width = width);
+        }
+        println!();
+    }
+}
+
+/// Distance puis, sur demande, chemin d'édition et table
+fn report<T: Symbol>(a: &[T], b: &[T], args: &Args) {
+    let table = distance_table(a, b);
+    println!("Distance: {}", table[a.len()][b.len()]);
+
+    if args.path {
+        print_path(&edit_path(a, b, &table));
+    }
+    if args.matrix {
+        print_matrix(a, b, &table);
+    }
+}
+
+// ==========================================
+// MODE BATCH (--batch)
+// ==========================================
+
+/// Une paire "mot1,mot2" par ligne ; lignes vides et commentaires (#) ignorés
+fn run_batch(path: &str, unicode: bool) {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("Error: Could not read {}. {}", path, e);
+            process::exit(1);
+        }
+    };
+
+    for (n, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((a, b)) = line.split_once(',') else {
+            eprintln!("Warning: line {}: expected \"word1,word2\", skipped", n + 1);
+            continue;
+        };
+        let (a, b) = (a.trim(), b.trim());
+        let distance = if unicode { levenshtein_chars(a, b) } else { levenshtein(a, b) };
+        println!("{},{},{}", a, b, distance);
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+
+    // Mode batch : une distance par paire du fichier
+    if let Some(path) = &args.batch {
+        run_batch(path, args.unicode);
+        return;
+    }
+
+    // clap garantit la présence des deux mots hors --batch
+    let word1 = args.word1.as_deref().unwrap_or_default();
+    let word2 = args.word2.as_deref().unwrap_or_default();
+
+    // 1. Découpage en unités (octets ou caractères)
+    // 2. Table de distances, puis chemin et matrice sur demande
+    if args.unicode {
+        let a: Vec<char> = word1.chars().collect();
+        let b: Vec<char> = word2.chars().collect();
+        report(&a, &b, &args);
+    } else {
+        report(word1.as_bytes(), word2.as_bytes(), &args);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Applique les opérations dans l'ordre, aux positions annoncées
+    fn apply<T: Symbol>(word: &[T], ops: &[EditOp<T>]) -> Vec<T> {
+        let mut word = word.to_vec();
+        for op in ops {
+            match *op {
+                EditOp::Insert { pos, symbol } => word.insert(pos, symbol),
+                EditOp::Delete { pos, .. } => {
+                    word.remove(pos);
+                }
+                EditOp::Substitute { pos, to, .. } => word[pos] = to,
+            }
+        }
+        word
+    }
+
+    #[test]
+    fn kitten_sitting() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein_chars("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn empty_strings() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("abc", ""), 3);
+        assert_eq!(levenshtein_chars("", "été"), 3);
+    }
+
+    #[test]
+    fn multibyte_counts_bytes_or_chars() {
+        // "é" fait 2 octets : 2 substitutions en octets, 1 en caractères
+        assert_eq!(levenshtein("café", "cafe"), 2);
+        assert_eq!(levenshtein_chars("café", "cafe"), 1);
+    }
+
+    #[test]
+    fn edit_path_positions() {
+        let (a, b): (Vec<char>, Vec<char>) = ("kitten".chars().collect(), "sitting".chars().collect());
+        let ops = edit_path(&a, &b, &distance_table(&a, &b));
+        assert_eq!(ops.len(), 3);
+        assert!(matches!(ops[0], EditOp::Substitute { pos: 0, from: 'k', to: 's' }));
+        assert!(matches!(ops[1], EditOp::Substitute { pos: 4, from: 'e', to: 'i' }));
+        assert!(matches!(ops[2], EditOp::Insert { pos: 6, symbol: 'g' }));
+        assert_eq!(apply(&a, &ops), b);
+
+        // Suppressions et insertions mêlées : les positions restent valables dans l'ordre
+        let (a, b) = (b"abcdef".as_slice(), b"xbdyf".as_slice());
+        let ops = edit_path(a, b, &distance_table(a, b));
+        assert_eq!(ops.len(), levenshtein("abcdef", "xbdyf"));
+        assert_eq!(apply(a, &ops), b);
+    }
+}
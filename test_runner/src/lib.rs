@@ -0,0 +1,34 @@
+//! Harnais d'intégration : chaque outil du dépôt est compilé puis lancé comme
+//! par un utilisateur (voir tests/run_all.rs).
+//!
+//! Pas de workspace à la racine : rust_02 et rust_04 sont deux paquets nommés
+//! `hextool`, ce que cargo refuse dans un même workspace. Le harnais se lance donc
+//! depuis ce dossier avec `cargo test`, ou depuis la racine du dépôt avec
+//! `cargo test --manifest-path test_runner/Cargo.toml`.
+
+use assert_cmd::Command;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Crates déjà compilés pendant cette exécution (les tests tournent en parallèle)
+static BUILT: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+/// Commande qui lance le binaire `bin` du crate voisin `crate_dir`.
+/// Le crate est compilé (cargo build, profil debug) au premier appel.
+pub fn tool(crate_dir: &'static str, bin: &str) -> Command {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..").join(crate_dir);
+
+    // Verrou gardé pendant la compilation : un seul cargo build par crate
+    let mut built = BUILT.lock().unwrap_or_else(|e| e.into_inner());
+    if !built.contains(&crate_dir) {
+        let status = std::process::Command::new(env!("CARGO"))
+            .args(["build", "--quiet", "--manifest-path"])
+            .arg(dir.join("Cargo.toml"))
+            .status()
+            .expect("failed to run cargo");
+        assert!(status.success(), "cargo build failed for {}", crate_dir);
+        built.push(crate_dir);
+    }
+
+    Command::new(dir.join("target").join("debug").join(bin))
+}
@@ -0,0 +1,471 @@
+// Scénarios de bout en bout : code de sortie, stdout et stderr de chaque outil
+
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+use test_runner::tool;
+
+/// Dossier temporaire contenant `name` = `content`, supprimé à la fin du test
+fn temp_file(name: &str, content: &[u8]) -> (TempDir, String) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(name);
+    fs::write(&path, content).unwrap();
+    (dir, path.to_string_lossy().into_owned())
+}
+
+// ==========================================
+// rust_00 : rustyhello
+// ==========================================
+
+mod rustyhello {
+    use super::*;
+
+    fn cmd() -> assert_cmd::Command {
+        tool("rust_00", "rustyhello")
+    }
+
+    #[test]
+    fn greets_world_by_default() {
+        cmd().assert().success().stdout("Hello, World!\n").stderr("");
+    }
+
+    #[test]
+    fn upper_and_repeat() {
+        cmd()
+            .args(["Alice", "--upper", "-r", "2"])
+            .assert()
+            .success()
+            .stdout("HELLO, ALICE!\nHELLO, ALICE!\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn json_format() {
+        cmd()
+            .args(["Bob", "--format", "json"])
+            .assert()
+            .success()
+            .stdout("{\"message\":\"Hello, Bob!\"}\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn name_file_skips_blank_lines() {
+        let (_dir, names) = temp_file("names.txt", b"Ann\n\n  Ben  \n");
+        cmd()
+            .args(["--name-file", &names])
+            .assert()
+            .success()
+            .stdout("Hello, Ann!\nHello, Ben!\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn output_writes_file_instead_of_stdout() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out.txt");
+        cmd()
+            .args(["Joe", "--output"])
+            .arg(&out)
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+        assert_eq!(fs::read_to_string(out).unwrap(), "Hello, Joe!\n");
+    }
+
+    #[test]
+    fn template_needs_exactly_one_placeholder() {
+        cmd()
+            .args(["--template", "Hi {} {}"])
+            .assert()
+            .code(1)
+            .stderr(predicate::str::contains("expected exactly one"));
+    }
+}
+
+// ==========================================
+// rust_01 : wordfreq
+// ==========================================
+
+mod wordfreq {
+    use super::*;
+
+    fn cmd() -> assert_cmd::Command {
+        tool("rust_01", "wordfreq")
+    }
+
+    #[test]
+    fn counts_words_by_frequency() {
+        cmd()
+            .arg("the cat the dog the end")
+            .assert()
+            .success()
+            .stdout("Word frequency:\nthe: 3\ncat: 1\ndog: 1\nend: 1\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn top_limits_the_table() {
+        cmd()
+            .args(["a b a", "--top", "1"])
+            .assert()
+            .success()
+            .stdout("Top 1 words:\na: 2\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn ignore_case_merges_words() {
+        cmd()
+            .args(["Hello hello HELLO", "--ignore-case"])
+            .assert()
+            .success()
+            .stdout("Word frequency:\nhello: 3\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn reads_stdin_without_text_argument() {
+        cmd()
+            .write_stdin("apple apricot banana")
+            .assert()
+            .success()
+            .stdout("Word frequency:\napple: 1\napricot: 1\nbanana: 1\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn cosine_similarity_of_identical_texts() {
+        cmd()
+            .args(["x y", "--cosine-sim", "y x"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Cosine similarity: 1.0000 (identical)"))
+            .stderr("");
+    }
+
+    #[test]
+    fn phonetic_groups_by_soundex() {
+        cmd()
+            .args(["smith smyth", "--phonetic"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("S530: smith, smyth"))
+            .stderr("");
+    }
+
+    #[test]
+    fn autocomplete_lists_prefix_matches() {
+        cmd()
+            .args(["the then there other", "--autocomplete", "th"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Completions for 'th' (3 of 3):\nthe: 1\nthen: 1\nthere: 1\n"))
+            .stderr("");
+    }
+}
+
+// ==========================================
+// rust_02 : hextool (éditeur hexadécimal)
+// ==========================================
+
+mod hexeditor {
+    use super::*;
+
+    fn cmd() -> assert_cmd::Command {
+        tool("rust_02", "hextool")
+    }
+
+    #[test]
+    fn read_dumps_hex_and_ascii() {
+        let (_dir, file) = temp_file("data.bin", b"hello world");
+        cmd()
+            .args(["-f", &file, "-r"])
+            .assert()
+            .success()
+            .stdout("00000000: 68 65 6c 6c 6f 20 77 6f 72 6c 64                |hello world|\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn write_at_offset() {
+        let (_dir, file) = temp_file("data.bin", b"hello world");
+        cmd()
+            .args(["-f", &file, "-w", "4142", "-o", "0x2"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("✓ successfully written"))
+            .stderr("");
+        assert_eq!(fs::read(&file).unwrap(), b"heABo world");
+    }
+
+    #[test]
+    fn find_exit_code_follows_grep() {
+        let (_dir, file) = temp_file("data.bin", b"hello world");
+        cmd().args(["-f", &file, "--find", "776f"]).assert().code(0).stderr("");
+        cmd().args(["-f", &file, "--find", "ffff"]).assert().code(1);
+    }
+
+    #[test]
+    fn md5_checksum() {
+        let (_dir, file) = temp_file("data.bin", b"abc");
+        cmd()
+            .args(["-f", &file, "--checksum", "md5"])
+            .assert()
+            .success()
+            .stdout(predicate::str::ends_with("900150983cd24fb0d6963f7d28e17f72\n"))
+            .stderr("");
+    }
+
+    #[test]
+    fn xor_then_undo_restores_file() {
+        let (_dir, file) = temp_file("data.bin", b"secret");
+        cmd().args(["-f", &file, "--xor", "ff"]).assert().success().stderr("");
+        assert_ne!(fs::read(&file).unwrap(), b"secret");
+
+        cmd().args(["-f", &file, "--undo"]).assert().success().stderr("");
+        assert_eq!(fs::read(&file).unwrap(), b"secret");
+    }
+
+    #[test]
+    fn hex_stdin_read() {
+        cmd()
+            .args(["--hex-stdin", "-r"])
+            .write_stdin("41 42 43\n")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("41 42 43").and(predicate::str::contains("|ABC|")))
+            .stderr("");
+    }
+
+    #[test]
+    fn hex_stdin_temp_file_removed_on_failure_exit() {
+        let tmp = tempfile::tempdir().unwrap();
+        cmd()
+            .env("TMPDIR", tmp.path())
+            .args(["--hex-stdin", "--find", "7a7a"])
+            .write_stdin("68656c6c6f\n")
+            .assert()
+            .code(1);
+        assert_eq!(fs::read_dir(tmp.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn invalid_offset_is_rejected() {
+        let (_dir, file) = temp_file("data.bin", b"abc");
+        cmd()
+            .args(["-f", &file, "-r", "-o", "0xZZ"])
+            .assert()
+            .code(1)
+            .stderr(predicate::str::contains("Invalid hex offset: 0xZZ"));
+    }
+}
+
+// ==========================================
+// rust_03 : streamcipher (chat chiffré)
+// ==========================================
+
+mod streamcipher {
+    use super::*;
+
+    fn cmd() -> assert_cmd::Command {
+        tool("rust_03", "streamcipher")
+    }
+
+    #[test]
+    fn help_lists_subcommands() {
+        cmd()
+            .arg("--help")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("server  Start server").and(predicate::str::contains("client  Connect to server")))
+            .stderr("");
+    }
+
+    #[test]
+    fn subcommand_is_required() {
+        cmd().assert().code(2).stderr(predicate::str::contains("Usage:"));
+    }
+
+    #[test]
+    fn client_needs_a_host() {
+        cmd().arg("client").assert().code(2).stderr(predicate::str::contains("<HOST>"));
+    }
+
+    #[test]
+    fn tls_conflicts_with_legacy() {
+        cmd()
+            .args(["--tls", "--legacy", "server"])
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("cannot be used with"));
+    }
+
+    #[test]
+    fn invalid_prime_is_rejected() {
+        cmd()
+            .args(["server", "--prime", "zz"])
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("invalid 64-bit hex value"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_client_reports_missing_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("missing.sock");
+        cmd()
+            .args(["client", "--unix"])
+            .arg(&socket)
+            .assert()
+            .code(1)
+            .stderr(predicate::str::contains("Failed to connect"));
+    }
+}
+
+// ==========================================
+// rust_04 : hextool (plus court chemin sur grille)
+// ==========================================
+
+mod pathfinder {
+    use super::*;
+
+    const GRID: &[u8] = b"01 01 09\n09 01 09\n09 01 01\n";
+
+    fn cmd() -> assert_cmd::Command {
+        tool("rust_04", "hextool")
+    }
+
+    #[test]
+    fn finds_minimum_cost_path() {
+        let (_dir, grid) = temp_file("grid.txt", GRID);
+        cmd()
+            .arg(&grid)
+            .assert()
+            .success()
+            .stdout(
+                predicate::str::contains("Total cost: 0x4 (4 decimal)")
+                    .and(predicate::str::contains("(0,0)->(1,0)->(1,1)->(1,2)->(2,2)")),
+            )
+            .stderr("");
+    }
+
+    #[test]
+    fn both_prints_maximum_path() {
+        let (_dir, grid) = temp_file("grid.txt", GRID);
+        cmd()
+            .args([grid.as_str(), "--both"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("MAXIMUM COST PATH"))
+            .stderr("");
+    }
+
+    #[test]
+    fn counts_distinct_shortest_paths() {
+        let (_dir, grid) = temp_file("grid.txt", GRID);
+        cmd()
+            .args([grid.as_str(), "--count-paths"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Distinct minimum cost paths: 1"))
+            .stderr("");
+    }
+
+    #[test]
+    fn set_overrides_a_cell() {
+        let (_dir, grid) = temp_file("grid.txt", GRID);
+        cmd()
+            .args([grid.as_str(), "--set", "1,1,FF"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("[OVERRIDE] (1,1) := 0xFF").and(predicate::str::contains("Total cost: 0x14")))
+            .stderr("");
+    }
+
+    #[test]
+    fn seeded_generation_is_deterministic() {
+        let run = || cmd().args(["--generate", "6x4", "--seed", "7"]).assert().success().stderr("").get_output().stdout.clone();
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn missing_map_file_fails() {
+        cmd()
+            .arg("does-not-exist.txt")
+            .assert()
+            .code(1)
+            .stderr(predicate::str::contains("Could not read file"));
+    }
+}
+
+// ==========================================
+// rust_05 : editdist
+// ==========================================
+
+mod editdist {
+    use super::*;
+
+    fn cmd() -> assert_cmd::Command {
+        tool("rust_05", "editdist")
+    }
+
+    #[test]
+    fn kitten_to_sitting() {
+        cmd()
+            .args(["--word1", "kitten", "--word2", "sitting"])
+            .assert()
+            .success()
+            .stdout("Distance: 3\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn path_lists_operations() {
+        cmd()
+            .args(["--word1", "kitten", "--word2", "sitting", "--path"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Edit path (3 operations):").and(predicate::str::contains("Insert 'g' at 6")))
+            .stderr("");
+    }
+
+    #[test]
+    fn unicode_compares_characters() {
+        cmd().args(["--word1", "café", "--word2", "cafe"]).assert().success().stdout("Distance: 2\n");
+        cmd()
+            .args(["--word1", "café", "--word2", "cafe", "--unicode"])
+            .assert()
+            .success()
+            .stdout("Distance: 1\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn matrix_is_printed() {
+        cmd()
+            .args(["--word1", "ab", "--word2", "b", "--matrix"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("DP matrix:"))
+            .stderr("");
+    }
+
+    #[test]
+    fn batch_csv() {
+        let (_dir, pairs) = temp_file("pairs.csv", b"# pairs\na,b\nflaw,lawn\n");
+        cmd()
+            .args(["--batch", &pairs])
+            .assert()
+            .success()
+            .stdout("a,b,1\nflaw,lawn,2\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn both_words_are_required() {
+        cmd().args(["--word1", "a"]).assert().code(2).stderr(predicate::str::contains("--word2"));
+    }
+}